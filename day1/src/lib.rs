@@ -0,0 +1,815 @@
+//! Day 1 dial rotation logic as a library.
+//!
+//! Instruction classification, the zero-hit counters and `process_lines` are
+//! public so integration tests and other binaries can drive the dial directly;
+//! `main.rs` only fetches input and prints answers.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Classifies the starting character of an instruction line.
+/// Used to determine whether the dial rotates right (R) or left (L).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineStart {
+    Right,
+    Left,
+    Other,
+}
+
+/// Classifies a line based on its first character.
+/// 
+/// Returns:
+/// - `LineStart::Right` if the line starts with 'R' (rotate right)
+/// - `LineStart::Left` if the line starts with 'L' (rotate left)
+/// - `LineStart::Other` for any other character or empty lines
+pub fn classify_line(line: &str) -> LineStart {
+    match line.as_bytes().first().copied() {
+        Some(b'R') => LineStart::Right,
+        Some(b'L') => LineStart::Left,
+        _ => LineStart::Other,
+    }
+}
+
+/// Number of dial positions in the puzzle (0-99).
+pub const DEFAULT_DIAL_SIZE: u32 = 100;
+
+/// Dial geometry and starting position.
+/// 
+/// Built with `DialConfig::new`, which rejects a dial without positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DialConfig {
+    /// Number of positions on the dial, non-zero (e.g. 100, or 360 for a degree dial)
+    size: u32,
+    /// Initial dial position (normalized to 0..size)
+    start: u32,
+}
+
+impl DialConfig {
+    /// Returns a config for a `size`-position dial, or `None` if `size` is 0.
+    pub fn new(size: u32, start: u32) -> Option<Self> {
+        (size > 0).then_some(DialConfig { size, start })
+    }
+
+    /// Number of positions on the dial.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Initial dial position as given (not yet normalized).
+    pub fn start(&self) -> u32 {
+        self.start
+    }
+}
+
+/// Results from processing a sequence of dial rotation instructions.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Stats {
+    /// Final position of the dial (0..size, 0-99 by default)
+    pub value: u32,
+    /// Times the dial passed through 0 during an instruction without stopping there
+    pub crossings: u32,
+    /// Instructions whose final position was exactly 0
+    pub exact_landings: u32,
+}
+
+impl Stats {
+    /// Total number of times the dial crossed or landed on position 0.
+    pub fn zero_hits(&self) -> u32 {
+        self.crossings + self.exact_landings
+    }
+
+    /// Adds one instruction's `hits` (as returned by `step`) ending at `value`.
+    /// 
+    /// A move that ends on 0 after touching it counts its final hit as an exact
+    /// landing; every other hit is a crossing.
+    fn record(&mut self, value: u32, hits: u32) {
+        self.value = value;
+        if value == 0 && hits > 0 {
+            self.exact_landings += 1;
+            self.crossings += hits - 1;
+        } else {
+            self.crossings += hits;
+        }
+    }
+}
+
+/// Processes a sequence of dial rotation instructions and tracks statistics.
+/// 
+/// The dial is modeled as a circular 0-99 range:
+/// - 'R' commands rotate clockwise (increment)
+/// - 'L' commands rotate counter-clockwise (decrement)
+/// - Tracks how many times the dial crosses or lands on position 0
+/// 
+/// # Arguments
+/// * `start` - Initial dial position (will be normalized to 0-99)
+/// * `lines` - Iterator of instruction lines (format: "R<number>" or "L<number>")
+/// 
+/// # Returns
+/// `Stats` containing the final dial position, zero crossings and exact landings.
+/// With no recognized instructions the dial stays at the normalized start.
+pub fn process_lines<'a, I>(start: u32, lines: I) -> Stats
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    process_dial(DialConfig { size: DEFAULT_DIAL_SIZE, start }, lines)
+}
+
+/// Maps any integer start, including negatives, onto a dial of `dial_size` positions.
+/// 
+/// Uses the Euclidean remainder, so -10 on a 100-position dial is 90 and
+/// i64::MIN still lands inside `0..dial_size`. Returns `None` if `dial_size` is 0.
+pub fn normalize_start(start: i64, dial_size: u32) -> Option<u32> {
+    (dial_size > 0).then(|| start.rem_euclid(dial_size as i64) as u32)
+}
+
+/// `process_lines` with an `i64` start, e.g. one computed from other input.
+/// 
+/// The start is normalized with `normalize_start`; everything else is
+/// identical to `process_lines`.
+pub fn process_lines_signed<'a, I>(start: i64, lines: I) -> Stats
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let start = normalize_start(start, DEFAULT_DIAL_SIZE).expect("default dial size is non-zero");
+    process_lines(start, lines)
+}
+
+/// `process_lines` for a dial with `config.size` positions instead of 100.
+/// 
+/// The start is normalized to `0..size` and wrap counting uses the same size,
+/// so a size of 100 behaves exactly like `process_lines`.
+pub fn process_dial<'a, I>(config: DialConfig, lines: I) -> Stats
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let size = config.size;
+    // Normalize starting position to 0..size
+    let mut stats = Stats { value: config.start % size, crossings: 0, exact_landings: 0 };
+
+    for line in lines {
+        for cmd in commands(line) {
+            if let Some((next, hits)) = step_command(stats.value, cmd, size) {
+                stats.record(next, hits);
+            }
+        }
+    }
+
+    stats
+}
+
+/// Like `process_lines`, but with `strict` set the first malformed command is
+/// an error instead of a warning.
+/// 
+/// Strict mode is `try_process_lines`; without it this is `process_lines` and
+/// never fails.
+pub fn process_lines_checked<'a, I>(start: u32, lines: I, strict: bool) -> Result<Stats, ParseError>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    if strict {
+        try_process_lines(start, lines)
+    } else {
+        Ok(process_lines(start, lines))
+    }
+}
+
+/// Splits an instruction line into its individual commands.
+/// 
+/// Anything after '#' is a comment. The rest is either one command (a line
+/// like "R 5" that parses as a whole, as before) or whitespace-separated
+/// commands like "R20 L5 R15". A comment-only line yields no commands, while
+/// other blank or unrecognized lines are passed through so `step` still warns.
+pub fn commands(line: &str) -> Vec<&str> {
+    let (code, has_comment) = match line.split_once('#') {
+        Some((code, _)) => (code, true),
+        None => (line, false),
+    };
+    let trimmed = code.trim();
+
+    if trimmed.is_empty() {
+        return if has_comment { Vec::new() } else { vec![code] };
+    }
+    if parse_command(trimmed).is_some() {
+        return vec![trimmed];
+    }
+    trimmed.split_whitespace().collect()
+}
+
+/// Parses one 'R<number>'/'L<number>' command, without warnings.
+fn parse_command(cmd: &str) -> Option<(LineStart, u32)> {
+    let dir = classify_line(cmd);
+    let rest = match dir {
+        LineStart::Right => cmd.strip_prefix('R')?,
+        LineStart::Left => cmd.strip_prefix('L')?,
+        LineStart::Other => return None,
+    };
+    rest.trim().parse().ok().map(|delta| (dir, delta))
+}
+
+/// A command `try_process_lines` could not parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 0-based index of the offending line in the input (shown 1-based by `Display`)
+    pub index: usize,
+    /// The command token that failed to parse
+    pub token: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid instruction '{}' on line {}", self.token, self.index + 1)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Checked counterpart of `process_lines`: the first malformed command is an
+/// error instead of a warning.
+/// 
+/// Both unrecognized commands ("X99") and bad numbers ("Rx") are rejected;
+/// blank and comment-only lines are still skipped. Nothing is printed.
+pub fn try_process_lines<'a, I>(start: u32, lines: I) -> Result<Stats, ParseError>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let size = DEFAULT_DIAL_SIZE;
+    let mut stats = Stats { value: start % size, crossings: 0, exact_landings: 0 };
+
+    for (i, line) in lines.into_iter().enumerate() {
+        for cmd in commands(line) {
+            if cmd.trim().is_empty() {
+                continue;
+            }
+            // Validate first so `step_command` never reaches its warning paths
+            let error = || ParseError { index: i, token: cmd.trim().to_string() };
+            parse_command(cmd).ok_or_else(error)?;
+            let (next, hits) = step_command(stats.value, cmd, size).ok_or_else(error)?;
+            stats.record(next, hits);
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Distance covered by a sequence of instructions, independent of dial size.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Travel {
+    /// Sum of all rotation amounts regardless of direction
+    pub total_steps: u64,
+    /// Signed sum of rotation amounts (right positive, left negative)
+    pub net: i64,
+}
+
+/// Sums the rotation amounts of every valid command in `lines`.
+/// 
+/// Runs alongside `process_lines` as a sanity check: on a dial of `size`
+/// positions, `(start + net).rem_euclid(size)` equals the final `value`.
+/// Invalid commands are skipped silently.
+pub fn travel<'a, I>(lines: I) -> Travel
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut travel = Travel::default();
+    for line in lines {
+        for (dir, delta) in commands(line).into_iter().filter_map(parse_command) {
+            travel.total_steps += delta as u64;
+            match dir {
+                LineStart::Right => travel.net += delta as i64,
+                _ => travel.net -= delta as i64,
+            }
+        }
+    }
+    travel
+}
+
+/// Applies one instruction line to the dial at `value` on a `size`-position dial.
+/// 
+/// Every command on the line (see `commands`) is applied in order. Returns
+/// `(new_value, zero_hits)` summed over the valid commands, or `None` if the
+/// line has none, leaving the dial unchanged. Invalid commands are skipped
+/// with a warning; on a dial of `size` 0 every command is skipped.
+pub fn step(value: u32, line: &str, size: u32) -> Option<(u32, u32)> {
+    let mut result: Option<(u32, u32)> = None;
+    for cmd in commands(line) {
+        let current = result.map_or(value, |(v, _)| v);
+        if let Some((next, hits)) = step_command(current, cmd, size) {
+            let total = result.map_or(0, |(_, h)| h) + hits;
+            result = Some((next, total));
+        }
+    }
+    result
+}
+
+/// Applies a single 'R'/'L' command to the dial at `value` on a `size`-position dial.
+/// 
+/// Returns `(new_value, zero_hits)` for a valid command, or `None`
+/// (with a warning) for commands that cannot be parsed, leaving the dial unchanged.
+/// A `size` of 0 also gives `None`: a dial without positions can't turn.
+fn step_command(value: u32, line: &str, size: u32) -> Option<(u32, u32)> {
+    match classify_line(line) {
+        LineStart::Right => {
+            let rest = line.strip_prefix('R')?;
+            if let Ok(delta) = rest.trim().parse::<u32>() {
+                // Count how many times we cross 0 when rotating right, then
+                // update position (use u64 to prevent overflow before modulo)
+                let hits = zero_hits_right(value, delta, size)?;
+                Some((((value as u64 + delta as u64) % size as u64) as u32, hits))
+            } else {
+                eprintln!("Warning: invalid number after R in line: {line}");
+                None
+            }
+        }
+        LineStart::Left => {
+            let rest = line.strip_prefix('L')?;
+            if let Ok(delta) = rest.trim().parse::<u32>() {
+                // Count how many times we cross 0 when rotating left, then
+                // update position (add size before subtracting to avoid underflow,
+                // in u64 so sizes above u32::MAX / 2 can't overflow)
+                let hits = zero_hits_left(value, delta, size)?;
+                let size = size as u64;
+                Some((((value as u64 + size - (delta as u64 % size)) % size) as u32, hits))
+            } else {
+                eprintln!("Warning: invalid number after L in line: {line}");
+                None
+            }
+        }
+        LineStart::Other => {
+            eprintln!("Warning: unrecognized line start: {line}");
+            None
+        }
+    }
+}
+
+/// Breaks the zero-hit total down per instruction line.
+/// 
+/// Returns one count per input line (aligned with the input), holding the
+/// zero crossings that line contributed; skipped lines contribute 0.
+/// The sum equals `process_lines(start, lines).zero_hits()`.
+pub fn zero_hits_per_line<'a, I>(start: u32, lines: I) -> Vec<u32>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut value = start % DEFAULT_DIAL_SIZE;
+    lines
+        .into_iter()
+        .map(|line| match step(value, line, DEFAULT_DIAL_SIZE) {
+            Some((next, hits)) => {
+                value = next;
+                hits
+            }
+            None => 0,
+        })
+        .collect()
+}
+
+/// Cumulative dial trace: the position after each prefix of `lines`.
+/// 
+/// Element `i` is the value after processing lines `0..=i` on a dial with
+/// `dial_size` positions, using the same per-command math as `process_dial`;
+/// lines without a recognized command repeat the previous position. The last
+/// element therefore equals the final `Stats.value` of a full run. A
+/// `dial_size` of 0 gives an empty trace.
+pub fn prefix_positions<'a, I>(start: u32, lines: I, dial_size: u32) -> Vec<u32>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let Some(config) = DialConfig::new(dial_size, start) else {
+        return Vec::new();
+    };
+    let mut value = config.start() % config.size();
+    lines
+        .into_iter()
+        .map(|line| {
+            for cmd in commands(line) {
+                if let Some((next, _)) = step_command(value, cmd, dial_size) {
+                    value = next;
+                }
+            }
+            value
+        })
+        .collect()
+}
+
+/// Processes instructions front-to-back, or back-to-front when `reverse` is set.
+/// 
+/// Useful when instructions arrive newest-first: the iterator is consumed from
+/// its back end without collecting or reversing into a new buffer. The per-move
+/// math is identical to `process_lines`; only the iteration order flips.
+pub fn process_lines_directed<'a, I>(start: u32, lines: I, reverse: bool) -> Stats
+where
+    I: IntoIterator<Item = &'a str>,
+    I::IntoIter: DoubleEndedIterator,
+{
+    let lines = lines.into_iter();
+    if reverse {
+        process_lines(start, lines.rev())
+    } else {
+        process_lines(start, lines)
+    }
+}
+
+/// Simulates `trials` random dial sequences and tallies their zero-hit totals.
+/// 
+/// Each trial is `moves_per_trial` random 'R'/'L' instructions with deltas in
+/// `1..=max_delta`, run through `process_dial` from `start` on a dial of
+/// `dial_size` positions. The result maps a
+/// zero-hit total to how many trials produced it, so the counts sum to `trials`.
+/// The same `seed` always gives the same distribution; a `dial_size` of 0 gives
+/// an empty map.
+pub fn zero_hit_distribution(
+    start: u32,
+    trials: u32,
+    moves_per_trial: usize,
+    max_delta: u32,
+    seed: u64,
+    dial_size: u32,
+) -> BTreeMap<u32, u32> {
+    let Some(config) = DialConfig::new(dial_size, start) else {
+        return BTreeMap::new();
+    };
+    let mut rng = SplitMix64(seed);
+    let mut distribution = BTreeMap::new();
+
+    for _ in 0..trials {
+        let lines: Vec<String> = (0..moves_per_trial)
+            .map(|_| {
+                let dir = if rng.next() & 1 == 0 { 'R' } else { 'L' };
+                let delta = 1 + (rng.next() % max_delta.max(1) as u64) as u32;
+                format!("{dir}{delta}")
+            })
+            .collect();
+
+        let stats = process_dial(config, lines.iter().map(String::as_str));
+        *distribution.entry(stats.zero_hits()).or_insert(0) += 1;
+    }
+
+    distribution
+}
+
+/// Minimal seeded PRNG (SplitMix64) for reproducible simulations.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// Calculates how many times the dial crosses 0 when rotating right (clockwise).
+/// 
+/// When rotating right from position `start` by `delta` steps, we cross 0 each time
+/// we complete a full `size`-position cycle. This is computed by integer division.
+/// 
+/// Example: Starting at 50, rotating right by 250 crosses 0 twice (at 100 and 200).
+/// Returns `None` for a dial without positions (`size` 0).
+pub fn zero_hits_right(start: u32, delta: u32, size: u32) -> Option<u32> {
+    (start as u64 + delta as u64)
+        .checked_div(size as u64)
+        .map(|hits| hits as u32)
+}
+
+/// Calculates how many times the dial crosses 0 when rotating left (counter-clockwise).
+/// 
+/// When rotating left from position `start` by `delta` steps:
+/// - If already at 0: count full cycles (delta / size)
+/// - If delta < start: no zero crossing
+/// - Otherwise: cross 0 once immediately, then count additional full cycles
+/// 
+/// Example: Starting at 5, rotating left by 7 crosses 0 once (goes 5→4→3→2→1→0→99→98).
+/// Returns `None` for a dial without positions (`size` 0).
+pub fn zero_hits_left(start: u32, delta: u32, size: u32) -> Option<u32> {
+    if size == 0 {
+        None
+    } else if start == 0 {
+        Some(delta / size)
+    } else if delta < start {
+        Some(0)
+    } else {
+        Some(1 + (delta - start) / size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test right rotation with multiple full cycles.
+    /// Starting at 50, rotating right 1000 steps = 10 full cycles.
+    /// Final position: (50 + 1000) % 100 = 50
+    #[test]
+    fn right_wraps_correctly() {
+        let stats = process_lines(50, ["R1000"]);
+        assert_eq!(
+            stats,
+            Stats {
+                value: 50,
+                crossings: 10,
+                exact_landings: 0,
+            }
+        );
+    }
+
+    /// Test right rotation landing exactly on 0.
+    /// Starting at 50, rotating right 950 steps lands on 0.
+    /// Crosses 0 at steps: 50, 150, 250, ..., 950 (10 times total)
+    #[test]
+    fn right_wraps_corner_case() {
+        let stats = process_lines(50, ["R950"]);
+        assert_eq!(
+            stats,
+            Stats {
+                value: 0,
+                crossings: 9,
+                exact_landings: 1,
+            }
+        );
+    }
+
+    /// Test that exact landings are split from crossings but still sum to the total.
+    /// R950 from 50 ends on 0 (1 landing among its 10 hits); R1000 ends on 50 (none).
+    #[test]
+    fn exact_landings_are_separated_from_crossings() {
+        let landed = process_lines(50, ["R950"]);
+        assert_eq!((landed.crossings, landed.exact_landings), (9, 1));
+        assert_eq!(landed.zero_hits(), 10);
+
+        let passed = process_lines(50, ["R1000"]);
+        assert_eq!((passed.crossings, passed.exact_landings), (10, 0));
+        assert_eq!(passed.zero_hits(), 10);
+
+        // Starting on 0 and not moving is not a landing
+        assert_eq!(process_lines(0, ["R0"]).exact_landings, 0);
+    }
+
+    /// Test several commands per line plus a trailing comment:
+    /// 90 → R20 → 10 → L5 → 5 → R15 → 20, same as one command per line.
+    #[test]
+    fn multiple_commands_per_line_with_comment() {
+        let stats = process_lines(90, ["R20 L5 R15 # go"]);
+        assert_eq!(stats.value, 20);
+        assert_eq!(stats, process_lines(90, ["R20", "L5", "R15"]));
+        assert_eq!(step(90, "R20 L5 R15 # go", DEFAULT_DIAL_SIZE), Some((20, 1)));
+
+        // Comment-only lines are ignored; "R 5" still reads as one command
+        assert_eq!(commands("# note"), Vec::<&str>::new());
+        assert_eq!(commands("R 5"), vec!["R 5"]);
+        assert_eq!(process_lines(0, ["# note", "R 5"]).value, 5);
+
+        // A landing on 0 mid-line is still an exact landing
+        let stats = process_lines(50, ["R50 R10"]);
+        assert_eq!((stats.value, stats.crossings, stats.exact_landings), (10, 0, 1));
+    }
+
+    /// Test left rotation wrapping around 0.
+    /// Starting at 5, rotating left 7 steps: 5→4→3→2→1→0→99→98
+    /// Crosses 0 once at step 6.
+    #[test]
+    fn left_wraps_correctly() {
+        let stats = process_lines(5, ["L7"]);
+        assert_eq!(
+            stats,
+            Stats {
+                value: 98,
+                crossings: 1,
+                exact_landings: 0,
+            }
+        );
+    }
+
+    /// Test left rotation landing exactly on 0.
+    /// Starting at 10, rotating left 10 steps lands precisely on 0.
+    /// Should count as 1 zero hit.
+    #[test]
+    fn zero_without_wrap_counts() {
+        let stats = process_lines(10, ["L10"]);
+        assert_eq!(
+            stats,
+            Stats {
+                value: 0,
+                crossings: 0,
+                exact_landings: 1,
+            }
+        );
+    }
+
+    /// Test a sequence of mixed right and left rotations.
+    /// 90 → R20 → 10 → L5 → 5 → R15 → 20
+    /// Only the first right rotation (90→10) crosses 0 once at position 0.
+    #[test]
+    fn mixed_sequence_combines_counts() {
+        let stats = process_lines(90, ["R20", "L5", "R15"]);
+        assert_eq!(
+            stats,
+            Stats {
+                value: 20,
+                crossings: 1,
+                exact_landings: 0,
+            }
+        );
+    }
+
+    /// Test that an empty instruction list leaves the dial at the normalized start.
+    /// Starting at 250 normalizes to 50 with no zero hits.
+    #[test]
+    fn empty_input_keeps_normalized_start() {
+        let stats = process_lines(250, std::iter::empty());
+        assert_eq!(
+            stats,
+            Stats {
+                value: 50,
+                crossings: 0,
+                exact_landings: 0,
+            }
+        );
+    }
+
+    /// Test that blank and unrecognized lines are skipped without panicking,
+    /// including for a start at the top of the u32 range (u32::MAX % 100 = 95).
+    #[test]
+    fn blank_and_other_lines_are_skipped() {
+        let stats = process_lines(u32::MAX, ["", "   ", "X5", "#"]);
+        assert_eq!(
+            stats,
+            Stats {
+                value: 95,
+                crossings: 0,
+                exact_landings: 0,
+            }
+        );
+    }
+
+    /// Test that the checked parser reports the offending line index and token,
+    /// and otherwise agrees with the lenient `process_lines`.
+    #[test]
+    fn try_process_lines_reports_bad_token() {
+        assert_eq!(
+            try_process_lines(50, ["X99"]),
+            Err(ParseError { index: 0, token: "X99".to_string() })
+        );
+        assert_eq!(
+            try_process_lines(50, ["R10", "", "L5 Rx # typo"]),
+            Err(ParseError { index: 2, token: "Rx".to_string() })
+        );
+
+        let err = try_process_lines(50, ["R10", "X99"]).unwrap_err();
+        assert_eq!(err.to_string(), "invalid instruction 'X99' on line 2");
+
+        let lines = ["L68", "L30", "R48", "", "L5 R60 # comment", "L55"];
+        assert_eq!(try_process_lines(50, lines), Ok(process_lines(50, lines)));
+    }
+
+    /// Test total and net travel, and that the net agrees with the final value.
+    #[test]
+    fn travel_sums_steps_and_net() {
+        let lines = ["R20", "L5", "R15"];
+        assert_eq!(travel(lines), Travel { total_steps: 40, net: 30 });
+
+        let lines = ["L68", "L30", "R48", "X5", "L5 R60 # comment", "L55"];
+        let t = travel(lines);
+        assert_eq!(t, Travel { total_steps: 266, net: -50 });
+        assert_eq!((50 + t.net).rem_euclid(100) as u32, process_lines(50, lines).value);
+    }
+
+    /// Test that an unrecognized line fails in strict mode but is skipped in lenient mode.
+    #[test]
+    fn strict_mode_rejects_other_lines() {
+        let lines = ["R10", "", "X5", "L20"];
+        assert_eq!(
+            process_lines_checked(50, lines, true),
+            Err(ParseError { index: 2, token: "X5".to_string() })
+        );
+        assert_eq!(process_lines_checked(50, lines, false), Ok(process_lines(50, lines)));
+        assert_eq!(
+            process_lines_checked(50, ["R10", "", "L20"], true),
+            Ok(Stats { value: 40, crossings: 0, exact_landings: 0 })
+        );
+    }
+
+    /// Test a 360-position dial: R720 from 0 wraps twice, and the start is
+    /// normalized by the configured size rather than 100.
+    #[test]
+    fn configurable_dial_size() {
+        let degrees = |start| DialConfig::new(360, start).unwrap();
+        assert_eq!(process_dial(degrees(0), ["R720"]), Stats { value: 0, crossings: 1, exact_landings: 1 });
+        assert_eq!(process_dial(degrees(0), ["L720"]), Stats { value: 0, crossings: 1, exact_landings: 1 });
+        assert_eq!(process_dial(degrees(400), ["R330"]), Stats { value: 10, crossings: 1, exact_landings: 0 });
+        assert_eq!(process_dial(degrees(150), ["R100"]), Stats { value: 250, crossings: 0, exact_landings: 0 });
+
+        let lines = ["R1000", "L75", "R30"];
+        assert_eq!(
+            process_dial(DialConfig::new(DEFAULT_DIAL_SIZE, 50).unwrap(), lines),
+            process_lines(50, lines)
+        );
+    }
+
+    /// Test that a fixed seed reproduces the same distribution and every trial is tallied.
+    #[test]
+    fn zero_hit_distribution_is_deterministic() {
+        let first = zero_hit_distribution(50, 200, 10, 250, 42, 100);
+        let second = zero_hit_distribution(50, 200, 10, 250, 42, 100);
+        assert_eq!(first, second);
+        assert_eq!(first.values().sum::<u32>(), 200);
+
+        // A single one-step move away from 0 never hits 0 in either direction
+        assert_eq!(zero_hit_distribution(0, 5, 1, 1, 7, 100), BTreeMap::from([(0, 5)]));
+    }
+
+    /// Test that reverse processing of [c, b, a] matches forward processing of [a, b, c].
+    #[test]
+    fn reversed_iteration_matches_forward() {
+        let forward = process_lines_directed(90, ["R20", "L5", "R15"], false);
+        let reversed = process_lines_directed(90, ["R15", "L5", "R20"], true);
+        assert_eq!(forward, reversed);
+        assert_eq!(forward, process_lines(90, ["R20", "L5", "R15"]));
+    }
+
+    /// Test the per-line zero-hit breakdown on the AoC example.
+    /// L68 (50->82), L30 (->52) no hit, R48 (->0) hit, L5, R60 (->55) hit,
+    /// L55 (->0) hit, L1, L99 (->0) hit, R14, L82 (->32) hit: 6 in total.
+    #[test]
+    fn zero_hits_per_line_breakdown() {
+        let lines = ["L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82"];
+        let per_line = zero_hits_per_line(50, lines);
+        assert_eq!(per_line, vec![1, 0, 1, 0, 1, 1, 0, 1, 0, 1]);
+        assert_eq!(per_line.iter().sum::<u32>(), 6);
+        assert_eq!(zero_hits_per_line(50, ["R1000", "X", "L10"]), vec![10, 0, 0]);
+    }
+
+    /// Full test case with the example from Advent of Code.
+    /// Tests a complex sequence of 10 instructions to verify correct
+    /// tracking of both final position and zero crossings.
+    #[test]
+    fn aoc_test() {
+        let stats = process_lines(50, ["L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99",
+        "R14", "L82"]);
+        assert_eq!(
+            stats,
+            Stats{
+                value: 32,
+                crossings: 3,
+                exact_landings: 3,
+            }
+        );
+        assert_eq!(stats.zero_hits(), 6);
+    }
+
+    /// Test the cumulative trace on the example: one position per line, ending at the full run's value.
+    #[test]
+    fn prefix_positions_trace_example() {
+        let lines = ["L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82"];
+        let trace = prefix_positions(50, lines, DEFAULT_DIAL_SIZE);
+        assert_eq!(trace, vec![82, 52, 0, 95, 55, 0, 99, 0, 14, 32]);
+        assert_eq!(trace.last(), Some(&process_lines(50, lines).value));
+
+        // Unrecognized and multi-command lines, on a non-default dial
+        let lines = ["R20 L5", "bogus", "L400"];
+        let config = DialConfig::new(360, 10).unwrap();
+        let trace = prefix_positions(config.start(), lines, config.size());
+        assert_eq!(trace, vec![25, 25, 345]);
+        assert_eq!(trace.last(), Some(&process_dial(config, lines).value));
+        assert!(prefix_positions(50, [], DEFAULT_DIAL_SIZE).is_empty());
+    }
+
+    /// Test that a dial without positions yields no trace and no rotations instead of panicking.
+    #[test]
+    fn zero_size_dial_is_rejected() {
+        assert!(prefix_positions(50, ["R10", "L5"], 0).is_empty());
+        assert_eq!(zero_hits_right(50, 10, 0), None);
+        assert_eq!(zero_hits_left(50, 10, 0), None);
+        assert_eq!(step(0, "R10", 0), None);
+        assert_eq!(zero_hits_right(50, 250, 100), Some(3));
+        assert_eq!(zero_hits_left(5, 7, 100), Some(1));
+    }
+
+    /// Test that negative and oversized signed starts wrap onto the dial before processing.
+    #[test]
+    fn signed_start_normalizes_onto_dial() {
+        assert_eq!(normalize_start(-10, DEFAULT_DIAL_SIZE), Some(90));
+        assert_eq!(normalize_start(-100, DEFAULT_DIAL_SIZE), Some(0));
+        assert_eq!(normalize_start(250, DEFAULT_DIAL_SIZE), Some(50));
+        assert_eq!(normalize_start(-1, 360), Some(359));
+        assert!(normalize_start(i64::MIN, DEFAULT_DIAL_SIZE).unwrap() < DEFAULT_DIAL_SIZE);
+        assert_eq!(normalize_start(-10, 0), None);
+
+        let lines = ["L68", "L30", "R48", "L5", "R60"];
+        assert_eq!(process_lines_signed(-10, lines), process_lines(90, lines));
+        assert_eq!(process_lines_signed(50, lines), process_lines(50, lines));
+    }
+
+    /// Test that a zero-size dial is rejected and that a dial larger than
+    /// u32::MAX / 2 turns left without overflowing.
+    #[test]
+    fn dial_size_extremes() {
+        assert_eq!(DialConfig::new(0, 5), None);
+        assert!(zero_hit_distribution(50, 10, 5, 100, 1, 0).is_empty());
+
+        let size = u32::MAX - 1;
+        let huge = DialConfig::new(size, size - 1).unwrap();
+        assert_eq!(process_dial(huge, ["L1"]), Stats { value: size - 2, crossings: 0, exact_landings: 0 });
+        assert_eq!(process_dial(huge, ["R1", "L3"]), Stats { value: size - 3, crossings: 0, exact_landings: 1 });
+    }
+}
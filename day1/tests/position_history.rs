@@ -0,0 +1,25 @@
+use day1::{classify_line, process_lines, step, LineStart, DEFAULT_DIAL_SIZE};
+
+const EXAMPLE: [&str; 10] = ["L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82"];
+
+/// Replays the example one `step` at a time through the public API and checks
+/// the recorded position history against the `process_lines` totals.
+#[test]
+fn position_history_matches_process_lines() {
+    let mut value = 50;
+    let mut zero_hits = 0;
+    let mut history = vec![value];
+
+    for line in EXAMPLE {
+        assert_ne!(classify_line(line), LineStart::Other);
+        let (next, hits) = step(value, line, DEFAULT_DIAL_SIZE).expect("valid instruction");
+        value = next;
+        zero_hits += hits;
+        history.push(value);
+    }
+
+    assert_eq!(history, [50, 82, 52, 0, 95, 55, 0, 99, 0, 14, 32]);
+    let stats = process_lines(50, EXAMPLE);
+    assert_eq!((stats.value, stats.zero_hits()), (value, zero_hits));
+    assert_eq!(zero_hits, 6);
+}
@@ -1,31 +1,38 @@
+use std::borrow::Cow;
 use std::env;
+use std::io::{self, BufRead};
+use std::sync::LazyLock;
 
-// Advent of Code 2025 Day 2 - URL for fetching puzzle input
-const INPUT_URL: &str = "https://adventofcode.com/2025/day/2/input";
+#[path = "../../tools/aoc_io.rs"]
+mod aoc_io;
+use aoc_io::{with_input_reader, write_answer};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Parse command-line argument to determine validation mode
     // Accepts "atleast", "at-least", or "at_least" for AtLeastDouble mode
-    let mode = parse_mode(std::env::args().nth(1).as_deref());
-
-    // Retrieve session cookie from environment variable for AOC authentication
-    let session = env::var("AOC_SESSION")
-        .map_err(|_| "AOC_SESSION environment variable is not set")?;
-    
-    // Fetch puzzle input from Advent of Code using authenticated session
-    let body = ureq::get(INPUT_URL)
-        .header("Cookie", &format!("session={session}"))
-        .call()?
-        .into_body()
-        .read_to_string()?;
+    // `--raw` prints only the bare number (for scripting)
+    // `--base=N` checks the digit patterns of each ID written in base N (default 10)
+    // `--chunk=N` sets how many IDs each parallel job of the rayon scan checks
+    let args: Vec<String> = env::args().skip(1).collect();
+    let raw = args.iter().any(|arg| arg == "--raw");
+    let mode = parse_mode(args.iter().find(|arg| !arg.starts_with("--")).map(String::as_str));
+    let mut scan = ScanConfig::default();
+    if let Some(value) = args.iter().find_map(|arg| arg.strip_prefix("--base=")) {
+        scan.base = parse_base(value)?;
+    }
+    if let Some(value) = args.iter().find_map(|arg| arg.strip_prefix("--chunk=")) {
+        scan.chunk = parse_chunk(value)?;
+    }
 
-    // Process all lines and sum invalid IDs based on selected mode
-    let sum = sum_of_invalid_ids(body.lines(), mode);
+    with_input_reader(2, |reader| {
+        // Stream the ranges and sum invalid IDs based on selected mode
+        let sum = sum_of_invalid_ids_from_reader(reader, mode, scan)?;
 
-    println!("Sum of invalid IDs: {}", sum);
+        write_answer(&mut io::stdout().lock(), "Sum of invalid IDs", sum, raw)?;
 
-    Ok(())
+        Ok(())
+    })
 }
 
 /// Defines validation modes for detecting invalid ID patterns.
@@ -35,28 +42,98 @@ enum InvalidMode {
     ExactDouble,
     /// Invalid if the ID repeats a pattern 2+ times (e.g., 5555, 123123, 123123123)
     AtLeastDouble,
+    /// Invalid if the ID fails the Luhn checksum (e.g., 79927398710)
+    Luhn,
+    /// Invalid if the smallest repeating unit appears exactly N times
+    /// (e.g., N=3: 123123123 and 777, but not 777777)
+    ExactRepeat(u32),
+    /// Invalid if a non-trivial cyclic rotation of the ID gives the ID back
+    /// (e.g., 123123 rotated by 3). A string equals its rotation by k exactly
+    /// when it has period gcd(k, len), so this flags the same IDs as
+    /// `AtLeastDouble`
+    Rotational,
+    /// Invalid if the ID reads the same backwards (e.g., 1221, 898, 7)
+    Palindrome,
+    /// Invalid if the smallest repeating unit appears between `min` and `max`
+    /// times inclusive (e.g., 2..=3: 123123 and 123123123, but not 5555)
+    RepeatBounded { min: u32, max: u32 },
 }
 
 /// Parses command-line argument to determine validation mode.
 /// 
+/// Accepts `atleast` (and spellings), `luhn`, `rotational`, `palindrome`/`palin`,
+/// `exact=N` for `ExactRepeat(N)`, or `MIN-MAX` (e.g. `2-3`) for `RepeatBounded`.
 /// Defaults to `ExactDouble` if no argument or unrecognized argument provided.
 fn parse_mode(arg: Option<&str>) -> InvalidMode {
     match arg {
         Some("atleast") | Some("at-least") | Some("at_least") => InvalidMode::AtLeastDouble,
+        Some("luhn") => InvalidMode::Luhn,
+        Some("rotational") => InvalidMode::Rotational,
+        Some("palindrome") | Some("palin") => InvalidMode::Palindrome,
+        Some(arg) if arg.starts_with("exact=") => match arg["exact=".len()..].parse() {
+            Ok(n) => InvalidMode::ExactRepeat(n),
+            Err(_) => InvalidMode::ExactDouble,
+        },
+        Some(arg) if arg.contains('-') => {
+            let (min, max) = arg.split_once('-').unwrap();
+            match (min.parse(), max.parse()) {
+                (Ok(min), Ok(max)) => InvalidMode::RepeatBounded { min, max },
+                _ => InvalidMode::ExactDouble,
+            }
+        }
         _ => InvalidMode::ExactDouble,
     }
 }
 
+/// Parses the `--base=N` value: a radix in 2..=36.
+fn parse_base(value: &str) -> Result<u32, String> {
+    match value.parse() {
+        Ok(base) if (2..=36).contains(&base) => Ok(base),
+        _ => Err(format!("Invalid base '{value}', expected 2..=36")),
+    }
+}
+
+/// Parses the `--chunk=N` value: a positive number of IDs.
+fn parse_chunk(value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(chunk) if chunk > 0 => Ok(chunk),
+        _ => Err(format!("Invalid chunk size '{value}', expected a positive integer")),
+    }
+}
+
+/// Default number of IDs per parallel job in the rayon scan.
+const DEFAULT_CHUNK: usize = 4096;
+
+/// How ranges are scanned for invalid IDs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ScanConfig {
+    /// Radix (2..=36) the ID digits are checked in; range bounds in the input are always decimal
+    base: u32,
+    /// IDs per job when the brute-force scan runs on rayon (`rayon` feature).
+    /// Small chunks add scheduling overhead, large ones hurt load balancing.
+    #[cfg_attr(not(feature = "rayon"), allow(dead_code))]
+    chunk: usize,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        ScanConfig { base: 10, chunk: DEFAULT_CHUNK }
+    }
+}
+
 /// Represents an inclusive range of ID numbers to validate.
+/// 
+/// Bounds are `u128` so IDs beyond `u64::MAX` (up to 39 digits) are accepted.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Range {
-    start: u64,
-    end: u64,
+    start: u128,
+    end: u128,
 }
 
 /// Parses a single range from a string in the format "start-end".
 /// 
-/// Returns `None` if the format is invalid or numbers can't be parsed.
+/// Returns `None` if the format is invalid or numbers can't be parsed
+/// (including bounds above `u128::MAX`).
 /// 
 /// Example: "55-56" -> Some(Range { start: 55, end: 56 })
 fn parse_range(part: &str) -> Option<Range> {
@@ -103,12 +180,23 @@ fn ranges(line: &str) -> impl Iterator<Item = Range> + '_ {
 /// - "111" -> false (odd length)
 fn is_repeating_pattern(s: &str) -> bool {
     // Only true when the string is exactly two repeated halves.
-    if s.len() % 2 != 0 {
+    if !s.len().is_multiple_of(2) {
         return false;
     }
 
     let mid = s.len() / 2;
-    &s[..mid] == &s[mid..]
+    s[..mid] == s[mid..]
+}
+
+/// Checks if a string reads the same forwards and backwards.
+/// 
+/// Examples:
+/// - "1221" -> true
+/// - "898" -> true
+/// - "7" -> true (single digits are trivially palindromes)
+/// - "1234" -> false
+fn is_palindrome(s: &str) -> bool {
+    s.bytes().eq(s.bytes().rev())
 }
 
 /// Checks if a string contains a pattern repeated at least twice.
@@ -125,18 +213,8 @@ fn is_repeating_pattern(s: &str) -> bool {
 fn is_repeating_at_least_twice(s: &str) -> bool {
     let len = s.len();
     
-    // Try each possible pattern size from 1 to len/2
-    for size in 1..=len / 2 {
-        // Pattern size must evenly divide total length
-        if len % size != 0 {
-            continue;
-        }
-        
-        let repeats = len / size;
-        if repeats < 2 {
-            continue;
-        }
-        
+    // Try each pattern size that evenly divides the length, from 1 to len/2
+    for &size in periods(len).iter() {
         // Check if all chunks match the first segment
         let segment = &s[..size];
         if s.as_bytes()
@@ -149,30 +227,311 @@ fn is_repeating_at_least_twice(s: &str) -> bool {
     false
 }
 
+/// Longest string the period table covers: a u128 ID written in base 2.
+const MAX_PERIOD_LEN: usize = 128;
+
+/// `proper_periods` for every length up to `MAX_PERIOD_LEN`, built on first use.
+static PERIODS: LazyLock<Vec<Vec<usize>>> =
+    LazyLock::new(|| (0..=MAX_PERIOD_LEN).map(proper_periods).collect());
+
+/// Pattern sizes that can tile a string of length `len` at least twice:
+/// the divisors of `len` from 1 to len/2, ascending.
+fn proper_periods(len: usize) -> Vec<usize> {
+    (1..=len / 2).filter(|&size| len.is_multiple_of(size)).collect()
+}
+
+/// Cached `proper_periods` for lengths up to `MAX_PERIOD_LEN`.
+/// 
+/// Every ID of a given length shares the same candidate periods, so they are
+/// computed once per length instead of by trial division for every ID.
+/// Longer lengths are not cached and return an empty slice.
+/// 
+/// Example: periods_for_length(6) -> [1, 2, 3]
+fn periods_for_length(len: usize) -> &'static [usize] {
+    PERIODS.get(len).map_or(&[], Vec::as_slice)
+}
+
+/// `periods_for_length`, falling back to computing `proper_periods` for
+/// lengths beyond the table.
+fn periods(len: usize) -> Cow<'static, [usize]> {
+    if len <= MAX_PERIOD_LEN {
+        Cow::Borrowed(periods_for_length(len))
+    } else {
+        Cow::Owned(proper_periods(len))
+    }
+}
+
+/// Counts how many times the smallest repeating unit of `s` repeats.
+/// 
+/// Pattern sizes are tried from 1 upward, so the first one that tiles the
+/// string is the smallest period. A string without a repeating unit is its
+/// own unit and returns 1.
+/// 
+/// Examples:
+/// - "123123123" -> 3 ("123")
+/// - "5555" -> 4 ("5")
+/// - "1234" -> 1
+fn min_repeat_count(s: &str) -> u32 {
+    let len = s.len();
+    for &size in periods(len).iter() {
+        if s.as_bytes()
+            .chunks(size)
+            .all(|chunk| chunk == &s.as_bytes()[..size])
+        {
+            return (len / size) as u32;
+        }
+    }
+    1
+}
+
+/// Checks if the smallest repeating unit of `s` repeats between `min` and
+/// `max` times inclusive (see `min_repeat_count`).
+/// 
+/// Examples:
+/// - ("123123123", 2, 3) -> true (3 repeats)
+/// - ("123123123", 2, 2) -> false
+/// - ("5555", 2, 3) -> false ("5" repeats 4 times)
+fn repeats_in_range(s: &str, min: u32, max: u32) -> bool {
+    (min..=max).contains(&min_repeat_count(s))
+}
+
+/// Checks if rotating a string by some 0 < k < len characters gives it back.
+/// 
+/// A rotation by k equals `(s + s)[k..k + len]`, so this looks for `s` inside
+/// `s + s` with the first and last character dropped (which excludes the
+/// trivial rotations by 0 and len).
+/// 
+/// Always agrees with `is_repeating_at_least_twice`; this is the rotation view
+/// of the same property.
+/// 
+/// Examples:
+/// - "123123" -> true (rotating by 3)
+/// - "5555" -> true (rotating by 1)
+/// - "123456" -> false
+/// - "7" -> false (no non-trivial rotation)
+fn has_rotational_symmetry(s: &str) -> bool {
+    let len = s.len();
+    if len < 2 {
+        return false;
+    }
+    let doubled = s.repeat(2);
+    doubled[1..2 * len - 1].contains(s)
+}
+
+/// Checks a number against the Luhn checksum.
+/// 
+/// Starting from the rightmost digit, every second digit is doubled (subtracting
+/// 9 when the result exceeds 9); the number is valid if the digit sum is a
+/// multiple of 10.
+/// 
+/// Examples:
+/// - 79927398713 -> true
+/// - 79927398710 -> false
+/// - 0 -> true
+fn luhn_valid(mut n: u128) -> bool {
+    let mut sum = 0;
+    let mut double = false;
+    loop {
+        let mut d = n % 10;
+        if double {
+            d *= 2;
+            if d > 9 {
+                d -= 9;
+            }
+        }
+        sum += d;
+        double = !double;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    sum.is_multiple_of(10)
+}
+
+/// Formats `n` in `base` (2..=36) with lowercase digits and no prefix.
+/// 
+/// Examples:
+/// - (10, 2) -> "1010"
+/// - (255, 16) -> "ff"
+/// - (0, 2) -> "0"
+fn to_base_string(mut n: u128, base: u32) -> String {
+    assert!((2..=36).contains(&base), "base must be in 2..=36, got {base}");
+    let mut digits = Vec::new();
+    loop {
+        digits.push(char::from_digit((n % base as u128) as u32, base).unwrap());
+        n /= base as u128;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
+}
+
 /// Determines if a number is invalid based on the validation mode.
 /// 
-/// Converts the number to a string and checks for repeating patterns.
-fn is_invalid(n: u64, mode: InvalidMode) -> bool {
-    let s = n.to_string();
+/// Repetition modes convert the number to a string and check for repeating
+/// patterns; `Luhn` checks the digit checksum directly.
+fn is_invalid(n: u128, mode: InvalidMode) -> bool {
+    is_invalid_in_base(n, mode, 10)
+}
+
+/// `is_invalid` with the pattern modes applied to the digits of `n` in `base`
+/// (2..=36) instead of decimal.
+/// 
+/// `Luhn` is a decimal checksum and ignores `base`.
+/// 
+/// Example: 10 is "1010" in base 2, so it is an `ExactDouble` there but not in base 10.
+fn is_invalid_in_base(n: u128, mode: InvalidMode, base: u32) -> bool {
+    let digits = || if base == 10 { n.to_string() } else { to_base_string(n, base) };
     match mode {
-        InvalidMode::ExactDouble => is_repeating_pattern(&s),
-        InvalidMode::AtLeastDouble => is_repeating_at_least_twice(&s),
+        InvalidMode::ExactDouble => is_repeating_pattern(&digits()),
+        InvalidMode::AtLeastDouble => is_repeating_at_least_twice(&digits()),
+        InvalidMode::Luhn => !luhn_valid(n),
+        InvalidMode::ExactRepeat(count) => min_repeat_count(&digits()) == count,
+        InvalidMode::Rotational => has_rotational_symmetry(&digits()),
+        InvalidMode::Palindrome => is_palindrome(&digits()),
+        InvalidMode::RepeatBounded { min, max } => repeats_in_range(&digits(), min, max),
     }
 }
 
-/// Sums all invalid numbers within an inclusive range.
+/// Sums the `len`-digit numbers (in `base`) in `range` that are a
+/// `period`-digit block repeated `len / period` times.
+/// 
+/// Such numbers are `v * m`, where `v` is the block and `m` places its copies
+/// (e.g. m = 1001001 for len 9, period 3 in base 10), so the matches form an
+/// arithmetic series over `v` and never need to be visited one by one.
+/// 
+/// Returns `None` if the sum does not fit in a `u128`.
+/// 
+/// Example: len 4, period 2 in 1000-1300 -> 1010 + 1111 + 1212
+fn sum_periodic_in_range(range: Range, len: u32, period: u32, base: u32) -> Option<u128> {
+    let base = base as u128;
+    // Built term by term: base^len itself overflows u128 for the longest IDs
+    let m: u128 = (0..len / period).map(|i| base.pow(period * i)).sum();
+    let lo = range.start.div_ceil(m).max(base.pow(period - 1));
+    let hi = (range.end / m).min(base.pow(period) - 1);
+    if lo > hi {
+        return Some(0);
+    }
+    // Halve whichever factor is even first: in base 2 a 64-bit block's
+    // (lo + hi) * count can overflow even when the series sum fits
+    let (ends, count) = (lo + hi, hi - lo + 1);
+    let series = if ends % 2 == 0 { (ends / 2).checked_mul(count) } else { ends.checked_mul(count / 2) };
+    series?.checked_mul(m)
+}
+
+/// Sums the `len`-digit numbers in `range` that repeat some block at least twice.
+/// 
+/// A number with period p also has period q for every multiple q of p that
+/// divides `len`, so the union over all proper periods is the union over the
+/// maximal ones, `len / r` for each prime r dividing `len`. Overlaps are
+/// removed by inclusion-exclusion over those primes (periods `len / (r1 * r2)`
+/// and so on). Digits are counted in `base`. Returns `None` if the sum does
+/// not fit in a `u128`.
+fn sum_repeating_in_range(range: Range, len: u32, base: u32) -> Option<u128> {
+    let primes: Vec<u32> = (2..=len)
+        .filter(|&p| len.is_multiple_of(p) && (2..p).all(|d| !p.is_multiple_of(d)))
+        .collect();
+
+    // Odd-sized subsets add, even-sized ones subtract; the total is never negative
+    let (mut added, mut removed) = (0u128, 0u128);
+    for subset in 1u32..(1 << primes.len()) {
+        let product: u32 = primes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| subset >> i & 1 == 1)
+            .map(|(_, p)| p)
+            .product();
+        let sum = sum_periodic_in_range(range, len, len / product, base)?;
+        if subset.count_ones() % 2 == 1 {
+            added = added.checked_add(sum)?;
+        } else {
+            removed = removed.checked_add(sum)?;
+        }
+    }
+    Some(added - removed)
+}
+
+/// Sums invalid numbers in `range` without visiting each one, for the modes
+/// whose invalid IDs can be enumerated directly.
+/// 
+/// Digit patterns are read in `base` (2..=36). Returns `None` for modes that
+/// need the per-number check. Sums saturate at `u128::MAX`. Expects
+/// `range.start <= range.end`.
+fn sum_invalid_enumerated(range: Range, mode: InvalidMode, base: u32) -> Option<u128> {
+    // Digits of u128::MAX in this base (39 in decimal, 128 in binary)
+    let lengths = 1..=u128::MAX.ilog(base as u128) + 1;
+    let per_length: Vec<Option<u128>> = match mode {
+        InvalidMode::ExactDouble => lengths
+            .filter(|len| len % 2 == 0)
+            .map(|len| sum_periodic_in_range(range, len, len / 2, base))
+            .collect(),
+        // Rotational symmetry and repeating at least twice are the same property
+        InvalidMode::AtLeastDouble | InvalidMode::Rotational => {
+            lengths.map(|len| sum_repeating_in_range(range, len, base)).collect()
+        }
+        _ => return None,
+    };
+    Some(
+        per_length
+            .into_iter()
+            .try_fold(0u128, |acc, sum| acc.checked_add(sum?))
+            .unwrap_or(u128::MAX),
+    )
+}
+
+/// Sums invalid numbers within an inclusive range by checking each one.
 /// 
 /// Iterates through [start, end] and sums numbers that match the invalid pattern.
-/// Returns 0 if start > end (with a warning).
-fn sum_invalid_in_range(range: Range, mode: InvalidMode) -> u64 {
+/// Saturates at `u128::MAX`. Expects `range.start <= range.end`.
+fn sum_invalid_brute(range: Range, mode: InvalidMode, base: u32) -> u128 {
+    (range.start..=range.end)
+        .filter(|&n| is_invalid_in_base(n, mode, base))
+        .fold(0, u128::saturating_add)
+}
+
+/// Parallel `sum_invalid_brute` on the rayon pool (`rayon` feature).
+/// 
+/// [start, end] is cut into consecutive chunks of `chunk` IDs (the last one may
+/// be shorter); rayon scans the chunks on separate threads with
+/// `sum_invalid_brute`. Saturating addition is associative, so the result is
+/// identical to the serial version for any `chunk`.
+/// Expects `range.start <= range.end` and `chunk > 0`.
+#[cfg(feature = "rayon")]
+fn sum_invalid_brute_par(range: Range, mode: InvalidMode, base: u32, chunk: usize) -> u128 {
+    use rayon::prelude::*;
+
+    let chunk = chunk as u128;
+    (0..=(range.end - range.start) / chunk)
+        .into_par_iter()
+        .map(|i| {
+            let start = range.start + i * chunk;
+            let end = start.saturating_add(chunk - 1).min(range.end);
+            sum_invalid_brute(Range { start, end }, mode, base)
+        })
+        .reduce(|| 0, u128::saturating_add)
+}
+
+/// Sums all invalid numbers within an inclusive range.
+/// 
+/// `ExactDouble`, `AtLeastDouble` and `Rotational` are summed in closed form per digit length,
+/// so ranges like 1-10000000000 are instant; other modes check every number,
+/// in parallel (in jobs of `scan.chunk` IDs) when built with the `rayon` feature.
+/// Digit patterns are read in `scan.base` (2..=36).
+/// Saturates at `u128::MAX`. Returns 0 if start > end (with a warning).
+fn sum_invalid_in_range(range: Range, mode: InvalidMode, scan: ScanConfig) -> u128 {
     if range.start > range.end {
         eprintln!("Warning: start greater than end in range: {:?}", range);
         return 0;
     }
 
-    (range.start..=range.end)
-        .filter(|&n| is_invalid(n, mode))
-        .sum()
+    #[cfg(feature = "rayon")]
+    let brute = |range, mode, base| sum_invalid_brute_par(range, mode, base, scan.chunk);
+    #[cfg(not(feature = "rayon"))]
+    let brute = sum_invalid_brute;
+
+    sum_invalid_enumerated(range, mode, scan.base).unwrap_or_else(|| brute(range, mode, scan.base))
 }
 
 /// Calculates the total sum of invalid IDs across all ranges in all lines.
@@ -185,14 +544,132 @@ fn sum_invalid_in_range(range: Range, mode: InvalidMode) -> u64 {
 /// # Arguments
 /// * `lines` - Iterator of input lines, each containing comma-separated ranges
 /// * `mode` - Validation mode (ExactDouble or AtLeastDouble)
-fn sum_of_invalid_ids<'a, I>(lines: I, mode: InvalidMode) -> u64
+/// * `scan` - Digit base and parallel chunk size (see `ScanConfig`)
+fn sum_of_invalid_ids<'a, I>(lines: I, mode: InvalidMode, scan: ScanConfig) -> u128
 where
     I: IntoIterator<Item = &'a str>,
 {
-    let mut sum: u64 = 0;
+    let mut sum: u128 = 0;
     for line in lines {
         for range in ranges(line) {
-            sum = sum.saturating_add(sum_invalid_in_range(range, mode));
+            sum = sum.saturating_add(sum_invalid_in_range(range, mode, scan));
+        }
+    }
+
+    sum
+}
+
+/// Sums and counts invalid IDs across all ranges in all lines.
+/// 
+/// Returns `(sum, count)`. Unlike `sum_of_invalid_ids` this checks every ID with
+/// `is_invalid`, so it is meant for puzzle-sized ranges. The sum saturates like
+/// `sum_of_invalid_ids`.
+#[allow(dead_code)]
+fn tally_invalid<'a, I>(lines: I, mode: InvalidMode) -> (u128, u64)
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut sum: u128 = 0;
+    let mut count: u64 = 0;
+    for line in lines {
+        for range in ranges(line) {
+            if range.start > range.end {
+                eprintln!("Warning: start greater than end in range: {:?}", range);
+                continue;
+            }
+            for n in (range.start..=range.end).filter(|&n| is_invalid(n, mode)) {
+                sum = sum.saturating_add(n);
+                count += 1;
+            }
+        }
+    }
+
+    (sum, count)
+}
+
+/// Merges overlapping or adjacent ranges into sorted, disjoint ranges
+/// (the same merge as day5's `merge_intervals`).
+/// 
+/// Example: [100-150, 140-200, 201-210, 300-300] -> [100-210, 300-300]
+#[allow(dead_code)]
+fn merge_ranges(mut v: Vec<Range>) -> Vec<Range> {
+    v.sort_by(|a, b| a.start.cmp(&b.start).then_with(|| a.end.cmp(&b.end)));
+
+    let mut merged: Vec<Range> = Vec::with_capacity(v.len());
+
+    for range in v {
+        if let Some(last) = merged.last_mut() {
+            // Inclusive bounds: merge when overlapping or directly adjacent
+            if range.start <= last.end.saturating_add(1) {
+                last.end = last.end.max(range.end);
+                continue;
+            }
+        }
+        merged.push(range);
+    }
+    merged
+}
+
+/// Sums and counts the IDs that are covered by `merged` and invalid under `mode`.
+/// 
+/// Composite of day5 coverage and day2 validation: `merged` should come from
+/// `merge_ranges`, so an ID covered by several input ranges is only counted
+/// once. Every covered ID is checked with `is_invalid`. Returns `(sum, count)`.
+#[allow(dead_code)]
+fn tally_invalid_covered(merged: &[Range], mode: InvalidMode) -> (u128, u64) {
+    let mut sum: u128 = 0;
+    let mut count: u64 = 0;
+    for range in merged {
+        for n in (range.start..=range.end).filter(|&n| is_invalid(n, mode)) {
+            sum = sum.saturating_add(n);
+            count += 1;
+        }
+    }
+
+    (sum, count)
+}
+
+/// Calculates the total sum of invalid IDs read from `reader`.
+/// 
+/// Same result as `sum_of_invalid_ids` over the input's lines, but input is
+/// consumed one comma-separated chunk at a time, so even a multi-gigabyte
+/// single-line input is never held in memory. Newlines also separate ranges.
+fn sum_of_invalid_ids_from_reader<R: BufRead>(reader: R, mode: InvalidMode, scan: ScanConfig) -> io::Result<u128> {
+    let mut sum: u128 = 0;
+    for chunk in reader.split(b',') {
+        let chunk = chunk?;
+        let lines = String::from_utf8_lossy(&chunk);
+        sum = sum.saturating_add(sum_of_invalid_ids(lines.lines(), mode, scan));
+    }
+
+    Ok(sum)
+}
+
+/// Calculates a weighted total of invalid IDs across all ranges in all lines.
+/// 
+/// Each invalid ID contributes `id * weight_fn(len)`, where `len` is the number
+/// of decimal digits in the ID. Accumulation saturates like `sum_of_invalid_ids`.
+/// 
+/// # Arguments
+/// * `lines` - Iterator of input lines, each containing comma-separated ranges
+/// * `mode` - Validation mode (ExactDouble or AtLeastDouble)
+/// * `weight_fn` - Maps a decimal digit length to the weight applied to IDs of that length
+#[allow(dead_code)]
+fn weighted_sum<'a, I>(lines: I, mode: InvalidMode, weight_fn: impl Fn(usize) -> u64) -> u128
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut sum: u128 = 0;
+    for line in lines {
+        for range in ranges(line) {
+            if range.start > range.end {
+                eprintln!("Warning: start greater than end in range: {:?}", range);
+                continue;
+            }
+            for n in (range.start..=range.end).filter(|&n| is_invalid(n, mode)) {
+                let len = n.to_string().len();
+                sum = sum.saturating_add(n.saturating_mul(weight_fn(len) as u128));
+            }
         }
     }
 
@@ -207,7 +684,7 @@ mod tests {
     /// Range 55-56 contains only 55 ("55" = "5" + "5"), which is invalid.
     #[test]
     fn repeating_digits_invalid() {
-        let invalid_id_sum = sum_of_invalid_ids(["55-56"], InvalidMode::ExactDouble);
+        let invalid_id_sum = sum_of_invalid_ids(["55-56"], InvalidMode::ExactDouble, ScanConfig::default());
         assert_eq!(invalid_id_sum, 55);
     }
 
@@ -215,7 +692,7 @@ mod tests {
     /// 123123 = "123" + "123" (exact double), so it's invalid.
     #[test]
     fn repeating_chunk_invalid() {
-        let invalid_id_sum = sum_of_invalid_ids(["123123-123123"], InvalidMode::ExactDouble);
+        let invalid_id_sum = sum_of_invalid_ids(["123123-123123"], InvalidMode::ExactDouble, ScanConfig::default());
         assert_eq!(invalid_id_sum, 123123);
     }
 
@@ -223,7 +700,7 @@ mod tests {
     /// 123123123 has 3 repetitions of "123", not exactly 2, so it's valid.
     #[test]
     fn triple_repetition_is_valid() {
-        let invalid_id_sum = sum_of_invalid_ids(["123123123-123123123"], InvalidMode::ExactDouble);
+        let invalid_id_sum = sum_of_invalid_ids(["123123123-123123123"], InvalidMode::ExactDouble, ScanConfig::default());
         assert_eq!(invalid_id_sum, 0);
     }
 
@@ -231,7 +708,7 @@ mod tests {
     /// "111" has odd length so can't be split into two equal halves - valid.
     #[test]
     fn odd_length_same_digit_is_valid() {
-        let invalid_id_sum = sum_of_invalid_ids(["111-111"], InvalidMode::ExactDouble);
+        let invalid_id_sum = sum_of_invalid_ids(["111-111"], InvalidMode::ExactDouble, ScanConfig::default());
         assert_eq!(invalid_id_sum, 0);
     }
 
@@ -239,7 +716,7 @@ mod tests {
     /// Range 1-2 has no invalid IDs, range 55-56 has 55, total = 55.
     #[test]
     fn multiple_ranges_count_combines() {
-        let invalid_id_sum = sum_of_invalid_ids(["1-2, 55-56"], InvalidMode::ExactDouble);
+        let invalid_id_sum = sum_of_invalid_ids(["1-2, 55-56"], InvalidMode::ExactDouble, ScanConfig::default());
         assert_eq!(invalid_id_sum, 55);
     }
     
@@ -249,7 +726,7 @@ mod tests {
     fn aoc_test_part1() {
         let invalid_id_sum = sum_of_invalid_ids(["11-22,95-115,998-1012,1188511880-1188511890,
         222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,
-        824824821-824824827,2121212118-2121212124"], InvalidMode::ExactDouble);
+        824824821-824824827,2121212118-2121212124"], InvalidMode::ExactDouble, ScanConfig::default());
         assert_eq!(invalid_id_sum, 1227775554);
     }
     
@@ -259,7 +736,7 @@ mod tests {
     fn aoc_test_part2() {
         let invalid_id_sum = sum_of_invalid_ids(["11-22,95-115,998-1012,1188511880-1188511890,
         222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,
-        824824821-824824827,2121212118-2121212124"], InvalidMode::AtLeastDouble);
+        824824821-824824827,2121212118-2121212124"], InvalidMode::AtLeastDouble, ScanConfig::default());
         assert_eq!(invalid_id_sum, 4174379265);
     }
 
@@ -267,7 +744,330 @@ mod tests {
     /// 123123123 has pattern "123" repeated 3 times (≥2), so it's invalid.
     #[test]
     fn triple_repetition_becomes_invalid_in_at_least_mode() {
-        let invalid_id_sum = sum_of_invalid_ids(["123123123-123123123"], InvalidMode::AtLeastDouble);
+        let invalid_id_sum = sum_of_invalid_ids(["123123123-123123123"], InvalidMode::AtLeastDouble, ScanConfig::default());
         assert_eq!(invalid_id_sum, 123123123);
     }
+
+    /// Test the Luhn checksum on known valid and invalid numbers.
+    #[test]
+    fn luhn_checksum() {
+        assert!(luhn_valid(79927398713));
+        assert!(luhn_valid(4539578763621486));
+        assert!(luhn_valid(18));
+        assert!(!luhn_valid(79927398710));
+        assert!(!luhn_valid(4539578763621487));
+        assert!(!luhn_valid(17));
+    }
+
+    /// In Luhn mode every ID failing the checksum counts as invalid.
+    /// In 10..=19 only 18 passes, so the sum is 145 - 18 = 127.
+    #[test]
+    fn luhn_mode_sums_failing_ids() {
+        assert_eq!(parse_mode(Some("luhn")), InvalidMode::Luhn);
+        assert_eq!(sum_of_invalid_ids(["10-19"], InvalidMode::Luhn, ScanConfig::default()), 127);
+    }
+
+    /// Test the cached period table against plain trial division.
+    #[test]
+    fn cached_periods_match_trial_division() {
+        assert_eq!(periods_for_length(6), &[1, 2, 3]);
+        assert_eq!(periods_for_length(7), &[1]);
+        assert!(periods_for_length(1).is_empty());
+        assert!(periods_for_length(MAX_PERIOD_LEN + 1).is_empty());
+        assert_eq!(periods(MAX_PERIOD_LEN + 2).as_ref(), proper_periods(MAX_PERIOD_LEN + 2).as_slice());
+
+        // The original check: every size up to len/2 that divides len
+        fn brute(s: &str) -> bool {
+            let len = s.len();
+            (1..=len / 2).any(|size| {
+                len.is_multiple_of(size) && s.as_bytes().chunks(size).all(|chunk| chunk == &s.as_bytes()[..size])
+            })
+        }
+        for n in (1..=200_000u32).chain([123123123, 1188511885, 2121212121, 824824824]) {
+            let digits = n.to_string();
+            assert_eq!(is_repeating_at_least_twice(&digits), brute(&digits), "{n}");
+        }
+        let long = "12".repeat(MAX_PERIOD_LEN);
+        assert!(is_repeating_at_least_twice(&long));
+        assert_eq!(min_repeat_count(&long), MAX_PERIOD_LEN as u32);
+
+        let range = Range { start: 1, end: 200_000 };
+        assert_eq!(
+            sum_invalid_in_range(range, InvalidMode::AtLeastDouble, ScanConfig::default()),
+            sum_invalid_brute(range, InvalidMode::AtLeastDouble, 10)
+        );
+    }
+
+    /// Test that ExactRepeat compares the smallest period's repeat count exactly.
+    /// 123123123 repeats "123" 3 times, so only N=3 marks it invalid.
+    #[test]
+    fn exact_repeat_count_mode() {
+        assert_eq!(parse_mode(Some("exact=3")), InvalidMode::ExactRepeat(3));
+        assert_eq!(min_repeat_count("123123123"), 3);
+        assert_eq!(min_repeat_count("5555"), 4);
+        assert_eq!(min_repeat_count("1234"), 1);
+        assert!(is_invalid(123123123, InvalidMode::ExactRepeat(3)));
+        assert!(!is_invalid(123123123, InvalidMode::ExactRepeat(2)));
+        assert!(!is_invalid(5555, InvalidMode::ExactRepeat(2)));
+    }
+
+    /// Test rotational symmetry, which flags exactly the `AtLeastDouble` IDs
+    /// (so 111 counts, unlike in `ExactDouble`), and its closed-form sum.
+    #[test]
+    fn rotational_symmetry_mode() {
+        assert_eq!(parse_mode(Some("rotational")), InvalidMode::Rotational);
+        assert!(has_rotational_symmetry("123123"));
+        assert!(!has_rotational_symmetry("123456"));
+        assert!(has_rotational_symmetry("111"));
+        assert!(!has_rotational_symmetry("7"));
+        assert!(!has_rotational_symmetry("1212121"));
+        assert_eq!(sum_of_invalid_ids(["110-125"], InvalidMode::Rotational, ScanConfig::default()), 111);
+        assert!((1..=100_000u128).all(|n| {
+            let s = n.to_string();
+            has_rotational_symmetry(&s) == is_repeating_at_least_twice(&s)
+        }));
+        let range = Range { start: 1, end: 10_000_000_000 };
+        assert_eq!(
+            sum_invalid_in_range(range, InvalidMode::Rotational, ScanConfig::default()),
+            sum_invalid_in_range(range, InvalidMode::AtLeastDouble, ScanConfig::default())
+        );
+    }
+
+    /// Test the enumerated sums against the per-number check on small ranges,
+    /// including ranges that start and end mid-length.
+    #[test]
+    fn enumerated_sum_matches_brute_force() {
+        let bounds = [(1, 9), (1, 999_999), (95, 1012), (998, 1_012_013), (1_188_511_870, 1_188_511_890)];
+        for (start, end) in bounds {
+            let range = Range { start, end };
+            for mode in [InvalidMode::ExactDouble, InvalidMode::AtLeastDouble] {
+                assert_eq!(
+                    sum_invalid_enumerated(range, mode, 10),
+                    Some(sum_invalid_brute(range, mode, 10)),
+                    "{mode:?} over {start}-{end}"
+                );
+            }
+        }
+        assert_eq!(sum_invalid_enumerated(Range { start: 1, end: 99 }, InvalidMode::Luhn, 10), None);
+    }
+
+    /// Test that the rayon scan matches the serial one, including the
+    /// string-based modes that always take the brute-force path.
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_brute_matches_serial() {
+        let range = Range { start: 1, end: 1_000_000 };
+        for mode in [
+            InvalidMode::ExactDouble,
+            InvalidMode::AtLeastDouble,
+            InvalidMode::Luhn,
+            InvalidMode::Palindrome,
+        ] {
+            assert_eq!(
+                sum_invalid_brute_par(range, mode, 10, DEFAULT_CHUNK),
+                sum_invalid_brute(range, mode, 10),
+                "{mode:?}"
+            );
+        }
+    }
+
+    /// Test that the parallel chunk size only changes scheduling, never the sum.
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_chunk_sizes_agree() {
+        let range = Range { start: 1, end: 200_000 };
+        let expected = sum_invalid_brute(range, InvalidMode::Luhn, 10);
+        for chunk in [1, 7, 1000, DEFAULT_CHUNK, 1 << 20] {
+            assert_eq!(sum_invalid_brute_par(range, InvalidMode::Luhn, 10, chunk), expected, "chunk {chunk}");
+            let scan = ScanConfig { chunk, ..ScanConfig::default() };
+            assert_eq!(sum_invalid_in_range(range, InvalidMode::Luhn, scan), expected, "chunk {chunk}");
+        }
+
+        // The last chunk is clipped at the range end, even at the top of u128
+        let top = Range { start: u128::MAX - 9, end: u128::MAX };
+        assert_eq!(sum_invalid_brute_par(top, InvalidMode::Luhn, 10, 4), sum_invalid_brute(top, InvalidMode::Luhn, 10));
+
+        assert_eq!(parse_chunk("64"), Ok(64));
+        assert!(parse_chunk("0").is_err());
+        assert!(parse_chunk("-1").is_err());
+    }
+
+    /// Test that a ten-billion-wide range is summed without iterating.
+    /// Exact doubles up to 10 digits are v * (10^h + 1) for every h-digit v, h = 1..=5.
+    #[test]
+    fn huge_range_sums_in_closed_form() {
+        assert_eq!(sum_of_invalid_ids(["1-10000000000"], InvalidMode::ExactDouble, ScanConfig::default()), 495495949990950);
+        assert!(
+            sum_of_invalid_ids(["1-10000000000"], InvalidMode::AtLeastDouble, ScanConfig::default())
+                > sum_of_invalid_ids(["1-10000000000"], InvalidMode::ExactDouble, ScanConfig::default())
+        );
+    }
+
+    /// Test palindrome mode, where every single-digit ID is invalid.
+    #[test]
+    fn palindrome_mode() {
+        assert_eq!(parse_mode(Some("palindrome")), InvalidMode::Palindrome);
+        assert_eq!(parse_mode(Some("palin")), InvalidMode::Palindrome);
+        assert!(is_invalid(121, InvalidMode::Palindrome));
+        assert!(is_invalid(1221, InvalidMode::Palindrome));
+        assert!(!is_invalid(1234, InvalidMode::Palindrome));
+        assert!((0..10).all(|n| is_invalid(n, InvalidMode::Palindrome)));
+        assert_eq!(sum_of_invalid_ids(["8-12"], InvalidMode::Palindrome, ScanConfig::default()), 8 + 9 + 11);
+    }
+
+    /// Test counting alongside summing: 55 is the only exact double in 55-56.
+    #[test]
+    fn tally_counts_and_sums() {
+        assert_eq!(tally_invalid(["55-56"], InvalidMode::ExactDouble), (55, 1));
+        let lines = ["11-22,95-115", "998-1012"];
+        let (sum, count) = tally_invalid(lines, InvalidMode::ExactDouble);
+        assert_eq!(sum, sum_of_invalid_ids(lines, InvalidMode::ExactDouble, ScanConfig::default()));
+        assert_eq!(count, 4); // 11, 22, 99, 1010
+    }
+
+    /// Test bounded repetition: 123123123 has 3 repeats of its smallest unit.
+    #[test]
+    fn repeat_bounded_mode() {
+        assert_eq!(parse_mode(Some("2-3")), InvalidMode::RepeatBounded { min: 2, max: 3 });
+        assert_eq!(parse_mode(Some("2-x")), InvalidMode::ExactDouble);
+        assert!(!repeats_in_range("123123123", 2, 2));
+        assert!(repeats_in_range("123123123", 2, 3));
+        assert!(!repeats_in_range("5555", 2, 3));
+        assert!(!is_invalid(123123123, InvalidMode::RepeatBounded { min: 2, max: 2 }));
+        assert!(is_invalid(123123123, InvalidMode::RepeatBounded { min: 2, max: 3 }));
+        assert_eq!(
+            sum_of_invalid_ids(["95-115"], InvalidMode::RepeatBounded { min: 2, max: 2 }, ScanConfig::default()),
+            99
+        );
+    }
+
+    /// Test pattern checks in other bases: 10 is "1010" in binary.
+    #[test]
+    fn patterns_in_other_bases() {
+        assert_eq!(to_base_string(10, 2), "1010");
+        assert_eq!(to_base_string(255, 16), "ff");
+        assert_eq!(to_base_string(0, 2), "0");
+        assert!(is_invalid_in_base(10, InvalidMode::ExactDouble, 2));
+        assert!(!is_invalid_in_base(10, InvalidMode::ExactDouble, 10));
+        assert!(is_invalid_in_base(0xabab, InvalidMode::ExactDouble, 16));
+        assert!(is_invalid_in_base(0b101101101, InvalidMode::AtLeastDouble, 2));
+        assert_eq!(is_invalid_in_base(1212, InvalidMode::ExactDouble, 10), is_invalid(1212, InvalidMode::ExactDouble));
+    }
+
+    /// Test coverage plus validation on [100, 200]: no 3-digit ID is an exact
+    /// double, while 111 (three 1s) is invalid in AtLeastDouble mode.
+    #[test]
+    fn invalid_ids_within_merged_ranges() {
+        let merged = merge_ranges(vec![Range { start: 140, end: 200 }, Range { start: 100, end: 150 }]);
+        assert_eq!(merged, vec![Range { start: 100, end: 200 }]);
+
+        assert_eq!(tally_invalid_covered(&merged, InvalidMode::ExactDouble), (0, 0));
+        assert_eq!(tally_invalid_covered(&merged, InvalidMode::AtLeastDouble), (111, 1));
+
+        // Overlaps and adjacency collapse, so 99 and 1010 are counted once each
+        let merged = merge_ranges(vec![
+            Range { start: 95, end: 1000 },
+            Range { start: 1001, end: 1012 },
+            Range { start: 90, end: 100 },
+        ]);
+        assert_eq!(merged, vec![Range { start: 90, end: 1012 }]);
+        assert_eq!(tally_invalid_covered(&merged, InvalidMode::ExactDouble), (99 + 1010, 2));
+    }
+
+    /// Test that streaming from a `BufRead` matches summing the collected string,
+    /// with ranges split across lines and a trailing newline.
+    #[test]
+    fn reader_sum_matches_collected_lines() {
+        let body = "11-22,95-115,998-1012\n1188511880-1188511890,222220-222224\n38593856-38593862\n";
+        for mode in [InvalidMode::ExactDouble, InvalidMode::AtLeastDouble] {
+            let streamed = sum_of_invalid_ids_from_reader(io::Cursor::new(body), mode, ScanConfig::default()).unwrap();
+            assert_eq!(streamed, sum_of_invalid_ids(body.lines(), mode, ScanConfig::default()));
+        }
+
+        // A tiny buffer forces chunks to be stitched across many reads
+        let reader = std::io::BufReader::with_capacity(4, body.as_bytes());
+        assert_eq!(
+            sum_of_invalid_ids_from_reader(reader, InvalidMode::ExactDouble, ScanConfig::default()).unwrap(),
+            sum_of_invalid_ids(body.lines(), InvalidMode::ExactDouble, ScanConfig::default())
+        );
+    }
+
+    /// Test that totals above u64::MAX are exact with the u128 accumulator.
+    /// 10000000001000000000 and 10000000011000000001 are both exact doubles
+    /// (each ~1e19); their sum (~2e19) exceeds u64::MAX (~1.8e19).
+    #[test]
+    fn u128_sum_does_not_saturate() {
+        let line = "10000000001000000000-10000000001000000000,10000000011000000001-10000000011000000001";
+        let exact: u128 = 10000000001000000000 + 10000000011000000001;
+        assert!(exact > u64::MAX as u128);
+        assert_eq!(sum_of_invalid_ids([line], InvalidMode::ExactDouble, ScanConfig::default()), exact);
+    }
+
+    /// Test IDs beyond u64::MAX: the first exact double above it is
+    /// 1844674408 repeated, and 39-digit bounds still parse.
+    #[test]
+    fn ids_beyond_u64_max() {
+        let above = u64::MAX as u128 + 1;
+        let line = format!("{above}-18446744090000000000");
+        assert_eq!(sum_of_invalid_ids([line.as_str()], InvalidMode::ExactDouble, ScanConfig::default()), 18446744081844674408);
+        assert_eq!(
+            sum_invalid_enumerated(Range { start: above, end: above + 100 }, InvalidMode::AtLeastDouble, 10),
+            Some(sum_invalid_brute(Range { start: above, end: above + 100 }, InvalidMode::AtLeastDouble, 10))
+        );
+
+        // 38 digits: a 19-digit block twice
+        let id: u128 = 12345678901234567891234567890123456789;
+        let line = format!("{}-{}", id - 5, id + 5);
+        assert_eq!(sum_of_invalid_ids([line.as_str()], InvalidMode::ExactDouble, ScanConfig::default()), id);
+
+        let max = u128::MAX.to_string();
+        assert_eq!(max.len(), 39);
+        assert_eq!(parse_range(&format!("{max}-{max}")), Some(Range { start: u128::MAX, end: u128::MAX }));
+        assert_eq!(parse_range(&format!("1-{max}0")), None);
+
+        // The full u128 range overflows the total, which saturates instead of wrapping
+        let line = format!("1-{max}");
+        assert_eq!(sum_of_invalid_ids([line.as_str()], InvalidMode::AtLeastDouble, ScanConfig::default()), u128::MAX);
+    }
+
+    /// Test weighting by digit length.
+    /// Range 95-1012 holds invalid IDs 99 (2 digits) and 1010 (4 digits).
+    /// Weighting 4-digit IDs double gives 99 * 1 + 1010 * 2 = 2119.
+    #[test]
+    fn weighted_sum_doubles_longer_ids() {
+        let weight = |len: usize| if len >= 4 { 2 } else { 1 };
+        let weighted = weighted_sum(["95-1012"], InvalidMode::ExactDouble, weight);
+        assert_eq!(weighted, 99 + 1010 * 2);
+        assert_eq!(weighted_sum(["95-1012"], InvalidMode::ExactDouble, |_| 1),
+            sum_of_invalid_ids(["95-1012"], InvalidMode::ExactDouble, ScanConfig::default()));
+    }
+
+    /// Test range sums in non-decimal bases: closed form against brute force,
+    /// a hand-checked binary sum, and the `--base` parser.
+    #[test]
+    fn range_sums_in_other_bases() {
+        // 3 = 11, 10 = 1010 and 15 = 1111 in binary
+        assert_eq!(sum_of_invalid_ids(["1-20"], InvalidMode::ExactDouble, ScanConfig { base: 2, ..ScanConfig::default() }), 3 + 10 + 15);
+        assert_eq!(sum_of_invalid_ids(["1-20"], InvalidMode::ExactDouble, ScanConfig::default()), 11);
+
+        let range = Range { start: 1, end: 50_000 };
+        for base in [2, 3, 7, 16, 36] {
+            for mode in [InvalidMode::ExactDouble, InvalidMode::AtLeastDouble] {
+                assert_eq!(
+                    sum_invalid_enumerated(range, mode, base),
+                    Some(sum_invalid_brute(range, mode, base)),
+                    "{mode:?} in base {base}"
+                );
+            }
+        }
+
+        // 128-bit IDs: the series overflows and saturates instead of panicking
+        let top = Range { start: 1 << 127, end: u128::MAX };
+        assert_eq!(sum_invalid_in_range(top, InvalidMode::ExactDouble, ScanConfig { base: 2, ..ScanConfig::default() }), u128::MAX);
+
+        assert_eq!(parse_base("16"), Ok(16));
+        assert!(parse_base("1").is_err());
+        assert!(parse_base("37").is_err());
+        assert!(parse_base("hex").is_err());
+    }
 }
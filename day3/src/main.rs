@@ -1,23 +1,49 @@
+use num_bigint::BigUint;
+use std::cmp::Reverse;
 use std::env;
+use std::io;
 
-const INPUT_URL: &str = "https://adventofcode.com/2025/day/3/input";
+#[path = "../../tools/aoc_io.rs"]
+mod aoc_io;
+use aoc_io::{with_input, write_answer};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let session = env::var("AOC_SESSION")
-        .map_err(|_| "AOC_SESSION environment variable is not set")?;
+    // `--raw` prints only the bare number (for scripting)
+    // The first other argument is k: 2 for part 1, 12 (the default) for part 2
+    let args: Vec<String> = env::args().skip(1).collect();
+    let raw = args.iter().any(|arg| arg == "--raw");
+    // `--skip-non-digits` drops separators and stray characters instead of zeroing the line
+    let policy = if args.iter().any(|arg| arg == "--skip-non-digits") {
+        DigitPolicy::SkipOthers
+    } else {
+        DigitPolicy::Strict
+    };
+    let k = parse_k(args.iter().find(|arg| !arg.starts_with("--")).map(String::as_str))?;
+
+    with_input(3, |body| {
+        let total_jolts = calculate_total_jolts_with(body.lines(), k, policy);
 
-    let body = ureq::get(INPUT_URL)
-        .header("Cookie", &format!("session={session}"))
-        .call()?
-        .into_body()
-        .read_to_string()?;
+        write_answer(&mut io::stdout().lock(), "Total jolts", total_jolts, raw)?;
 
-    let total_jolts = calculate_total_jolts(body.lines(), 12);
+        Ok(())
+    })
+}
 
-    println!("Total jolts: {}", total_jolts);
+/// Number of digits selected per line when no k is given (part 2).
+const DEFAULT_K: usize = 12;
 
-    Ok(())
-    
+/// Parses the k argument.
+/// 
+/// A missing argument gives `DEFAULT_K`. Anything that is not a positive
+/// integer is an error, 0 included, since no line can produce a 0-digit value.
+fn parse_k(arg: Option<&str>) -> Result<usize, String> {
+    let Some(arg) = arg else {
+        return Ok(DEFAULT_K);
+    };
+    match arg.parse::<usize>() {
+        Ok(k) if k > 0 => Ok(k),
+        _ => Err(format!("Invalid k '{arg}', expected a positive integer")),
+    }
 }
 
 /// Finds the maximum 2-digit number from a string where digits must be in order.
@@ -69,34 +95,174 @@ fn max_two_digits_ordered(line: &str) -> Option <u8> {
 /// 2. Removing smaller digits from the stack if a larger digit appears (when budget allows)
 /// 3. Ensuring exactly k digits remain
 /// 
-/// Example: max_k_digits_ordered("987654321111111", 12) -> 987654321111
+/// Digits are interpreted in `radix` (2..=36), so with radix 16 'A'..'F' (or
+/// 'a'..'f') are valid digits with values 10-15. Comparisons use digit values,
+/// and the result accumulates by multiplying by the radix.
+/// 
+/// Example: max_k_digits_ordered("987654321111111", 12, 10) -> 987654321111
 ///          We remove the three smallest trailing '1's to keep 12 digits
+/// Example: max_k_digits_ordered("9A3F", 2, 16) -> 0xAF = 175
 /// 
 /// Time: O(n), Space: O(n) where n is the string length
-fn max_k_digits_ordered(line: &str, k: usize) -> Option<u128> {
+/// (see `max_k_digits_streaming` for a variant whose stack never exceeds k)
+fn max_k_digits_ordered(line: &str, k: usize, radix: u32) -> Option<u128> {
+    // Parse the selected digits; from_str_radix fails (None) on u128 overflow
+    let selected = max_k_selection(line, k, radix)?;
+    u128::from_str_radix(&selected, radix).ok()
+}
 
-    let bytes = line.as_bytes();
-    let n = bytes.len();
+/// What a line may contain besides digits of the radix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum DigitPolicy {
+    /// Any other character makes the line invalid
+    #[default]
+    Strict,
+    /// Other characters (spaces, separators) are dropped before selecting
+    SkipOthers,
+}
+
+/// `max_k_digits_ordered` with the line first filtered according to `policy`.
+/// 
+/// With `SkipOthers`, k digits are selected among the radix digits only, so
+/// they need not be adjacent in the original line.
+/// 
+/// Example: max_k_digits_ordered_with("98 76 54", 3, 10, DigitPolicy::SkipOthers) -> 987
+/// Example: max_k_digits_ordered_with("98 76 54", 3, 10, DigitPolicy::Strict) -> None
+fn max_k_digits_ordered_with(line: &str, k: usize, radix: u32, policy: DigitPolicy) -> Option<u128> {
+    match policy {
+        DigitPolicy::Strict => max_k_digits_ordered(line, k, radix),
+        DigitPolicy::SkipOthers => {
+            let digits: String = line.chars().filter(|ch| ch.is_digit(radix)).collect();
+            max_k_digits_ordered(&digits, k, radix)
+        }
+    }
+}
 
-    // Edge cases: can't form k digits if k is invalid or exceeds length
+/// Decimal `max_k_digits_ordered` without the u128 limit.
+/// 
+/// Same greedy selection, parsed into a `BigUint`, so k may exceed the 38
+/// digits a u128 can always hold. Returns `None` only for invalid input or k.
+/// 
+/// Example: max_k_digits_ordered_big(&"9".repeat(40), 40) -> 10^40 - 1
+#[allow(dead_code)]
+fn max_k_digits_ordered_big(line: &str, k: usize) -> Option<BigUint> {
+    let selected = max_k_selection(line, k, 10)?;
+    BigUint::parse_bytes(selected.as_bytes(), 10)
+}
+
+/// Decimal `max_k_digits_ordered` that returns the selected digits themselves.
+/// 
+/// The string keeps leading zeros, which the numeric value drops.
+/// 
+/// Example: max_k_digits_ordered_str("0009", 3) -> "009" (numerically 9)
+/// Example: max_k_digits_ordered_str("1009", 3) -> "109"
+#[allow(dead_code)]
+fn max_k_digits_ordered_str(line: &str, k: usize) -> Option<String> {
+    max_k_selection(line, k, 10)
+}
+
+/// The greedy monotonic-stack selection behind `max_k_digits_ordered`.
+/// 
+/// Returns the k selected characters in their original order (and case), or
+/// `None` if k is 0, exceeds the line, or a character is not a `radix` digit.
+fn max_k_selection(line: &str, k: usize, radix: u32) -> Option<String> {
+    // (digit value, original byte) pairs: compare by value, output the byte
+    let digits: Vec<(u32, u8)> = line
+        .bytes()
+        .map(|b| (b as char).to_digit(radix).map(|d| (d, b)))
+        .collect::<Option<_>>()?;
+
+    let selected = max_k_subsequence_by_key(&digits, k, |&(d, _)| d)?;
+    Some(selected.into_iter().map(|(_, b)| b as char).collect())
+}
+
+/// Picks the k items, in their original order, that form the largest
+/// sequence (compared lexicographically), e.g. letters or any `Ord` tokens.
+/// 
+/// Example: max_k_subsequence(&['b', 'a', 'c', 'b'], 3) -> ['b', 'c', 'b']
+#[allow(dead_code)]
+fn max_k_subsequence<T: Ord + Copy>(items: &[T], k: usize) -> Option<Vec<T>> {
+    max_k_subsequence_by_key(items, k, |&item| item)
+}
+
+/// `max_k_subsequence` comparing items by `key`; items with equal keys are
+/// never swapped for each other, so the earliest ones are kept.
+/// 
+/// Returns `None` if k is 0 or exceeds the number of items.
+/// 
+/// Time: O(n), Space: O(n) where n is the number of items
+fn max_k_subsequence_by_key<T: Copy, K: Ord>(items: &[T], k: usize, key: impl Fn(&T) -> K) -> Option<Vec<T>> {
+    let n = items.len();
+
+    // Edge cases: can't pick k items if k is invalid or exceeds length
     if k == 0 || k > n {
         return None;
     }
 
-    let mut to_remove = n - k;  // How many digits we must discard
-    let mut stack: Vec<u8> = Vec::with_capacity(n);
+    let mut to_remove = n - k;  // How many items we must discard
+    let mut stack: Vec<T> = Vec::with_capacity(n);
 
-    // Process each digit left-to-right
-    for &b in bytes {
-        // Validate input is all digits
-        if b < b'0' || b > b'9' {
-            return None;
+    // Process each item left-to-right
+    for &item in items {
+        // Greedy removal: pop smaller items when we see a larger one
+        // This maintains a monotonic decreasing stack for optimal selection
+        while let Some(last) = stack.last() {
+            if to_remove > 0 && key(last) < key(&item) {
+                stack.pop();
+                to_remove -= 1;
+            } else {
+                break;
+            }
         }
+        stack.push(item);
+    }
+
+    // Take exactly k items from the stack; any excess at the end are the smallest
+    stack.truncate(k);
+    Some(stack)
+}
 
-        let d = b - b'0';
+/// Streaming variant of `max_k_digits_ordered` over a byte iterator of `len` bytes.
+///
+/// The greedy choice needs to know how many digits may still be dropped, so
+/// the length is passed in (e.g. from file metadata) rather than read off the
+/// input; any iterator works, including the bytes of a reader, and the line is
+/// never materialized. Once the stack holds k digits, a digit that cannot
+/// displace anything is dropped immediately instead of being pushed and
+/// trimmed at the end, so the stack is bounded by k rather than n.
+///
+/// Returns `None` if the iterator does not yield exactly `len` bytes.
+///
+/// Time: O(n), Space: O(k)
+#[allow(dead_code)]
+fn max_k_digits_streaming(bytes: impl IntoIterator<Item = u8>, len: usize, k: usize, radix: u32) -> Option<u128> {
+    max_k_digits_streaming_peak(bytes, len, k, radix).map(|(value, _)| value)
+}
+
+/// Same as `max_k_digits_streaming`, also returning the peak stack length.
+#[allow(dead_code)]
+fn max_k_digits_streaming_peak(
+    bytes: impl IntoIterator<Item = u8>,
+    len: usize,
+    k: usize,
+    radix: u32,
+) -> Option<(u128, usize)> {
+    if k == 0 || k > len {
+        return None;
+    }
+
+    let mut to_remove = len - k;
+    let mut stack: Vec<u32> = Vec::with_capacity(k);
+    let mut peak = 0;
+    let mut seen = 0;
+
+    for b in bytes {
+        seen += 1;
+        if seen > len {
+            return None;
+        }
+        let d = (b as char).to_digit(radix)?;
 
-        // Greedy removal: pop smaller digits when we see a larger one
-        // This maintains a monotonic decreasing stack for optimal selection
         while let Some(&last) = stack.last() {
             if to_remove > 0 && last < d {
                 stack.pop();
@@ -105,26 +271,182 @@ fn max_k_digits_ordered(line: &str, k: usize) -> Option<u128> {
                 break;
             }
         }
-        stack.push(d);
-    }
 
-    // Remove any excess digits from the end (smallest values)
-    while to_remove > 0 {
-        stack.pop();
-        to_remove -= 1;
+        // A full stack means this digit loses to everything kept so far
+        if stack.len() < k {
+            stack.push(d);
+            peak = peak.max(stack.len());
+        } else {
+            to_remove -= 1;
+        }
     }
 
-    // Take exactly k digits from the stack
-    let digits = &stack[..k];
+    // The greedy choices assumed `len` bytes; any other count invalidates them
+    if seen != len {
+        return None;
+    }
 
-    // Convert digit array to u128 number with overflow checking
-    let mut value: u128= 0;
-    for &d in digits {
+    let mut value: u128 = 0;
+    for &d in &stack {
         value = value
-            .checked_mul(10)?
+            .checked_mul(radix as u128)?
             .checked_add(d as u128)?;
     }
-    Some(value)
+    Some((value, peak))
+}
+
+/// Finds the minimum k-digit number from a string of digits while preserving order.
+/// 
+/// Mirror image of `max_k_digits_ordered`: the same monotonic stack with the
+/// digit order reversed, so larger digits are popped when a smaller one appears.
+/// Leading zeros are allowed, so the numeric value may have fewer than k digits.
+/// 
+/// Example: min_k_digits_ordered("987654321111111", 2) -> 11
+/// Example: min_k_digits_ordered("10234", 2) -> 2 (selected digits "02")
+/// 
+/// Time: O(n), Space: O(n) where n is the string length
+fn min_k_digits_ordered(line: &str, k: usize) -> Option<u128> {
+    // Parse the selected digits; parse fails (None) on u128 overflow
+    min_k_selection(line, k)?.parse().ok()
+}
+
+/// `min_k_digits_ordered` that returns the selected digits themselves.
+/// 
+/// The smallest selection often starts with zeros, which the string keeps.
+/// 
+/// Example: min_k_digits_ordered_str("10234", 2) -> "02"
+#[allow(dead_code)]
+fn min_k_digits_ordered_str(line: &str, k: usize) -> Option<String> {
+    min_k_selection(line, k)
+}
+
+/// The greedy monotonic-stack selection behind `min_k_digits_ordered`.
+/// 
+/// The smallest selection is the largest one under reversed digit order, so
+/// this is `max_k_subsequence_by_key` with a `Reverse` key.
+/// 
+/// Returns the k selected decimal digits in their original order, or `None`
+/// if k is 0, exceeds the line, or a character is not a digit.
+fn min_k_selection(line: &str, k: usize) -> Option<String> {
+    let bytes = line.as_bytes();
+    if !bytes.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+
+    let selected = max_k_subsequence_by_key(bytes, k, |&b| Reverse(b))?;
+    Some(selected.into_iter().map(char::from).collect())
+}
+
+/// Largest modulus `max_k_digits_by_remainder` accepts: its table holds
+/// (k + 1) * m entries, so m is capped rather than sized by the caller.
+const MAX_REMAINDER_MODULUS: u64 = 10_000;
+
+/// Largest k-digit ordered selection for every remainder modulo `m`.
+/// 
+/// DP over positions x remainders: `best[j][r]` is the largest value of a
+/// j-digit subsequence of the prefix seen so far that is congruent to `r`.
+/// Appending digit `d` to `best[j - 1][r]` gives a j-digit candidate with
+/// remainder `(r * 10 + d) % m`; equal lengths compare numerically, so keeping
+/// only the maximum per cell is enough. `j` runs downward so a digit is used
+/// at most once per step.
+/// 
+/// Returns `result[r]` (`None` where no selection has remainder `r`), or `None`
+/// if k is 0, exceeds the line, m is 0 or above `MAX_REMAINDER_MODULUS`, the
+/// line has a non-digit, or a value overflows. Leading zeros are allowed, as
+/// in `min_k_digits_ordered`.
+/// 
+/// Time: O(n * k * m), Space: O(k * m)
+fn max_k_digits_by_remainder(line: &str, k: usize, m: u64) -> Option<Vec<Option<u128>>> {
+    let bytes = line.as_bytes();
+    if k == 0 || k > bytes.len() || !(1..=MAX_REMAINDER_MODULUS).contains(&m) {
+        return None;
+    }
+
+    let mut best: Vec<Vec<Option<u128>>> = vec![vec![None; m as usize]; k + 1];
+    best[0][0] = Some(0);
+
+    for (i, &b) in bytes.iter().enumerate() {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        let d = (b - b'0') as u64;
+
+        for j in (1..=k.min(i + 1)).rev() {
+            for r in 0..m {
+                let Some(prefix) = best[j - 1][r as usize] else {
+                    continue;
+                };
+                let value = prefix.checked_mul(10)?.checked_add(d as u128)?;
+                let slot = &mut best[j][((r * 10 + d) % m) as usize];
+                if slot.is_none_or(|current| value > current) {
+                    *slot = Some(value);
+                }
+            }
+        }
+    }
+
+    best.pop()
+}
+
+/// Finds the maximum k-digit ordered selection that is divisible by `m`.
+/// 
+/// Unlike the greedy `max_k_digits_ordered`, the best divisible selection can
+/// differ in early digits, so this uses the `max_k_digits_by_remainder` DP.
+/// 
+/// Example: max_k_digits_divisible("9431", 2, 3) -> 93 (the greedy 94 is not divisible)
+#[allow(dead_code)]
+fn max_k_digits_divisible(line: &str, k: usize, m: u64) -> Option<u128> {
+    max_k_digits_by_remainder(line, k, m)?[0]
+}
+
+/// Finds the largest remainder modulo `m` reachable by a k-digit ordered
+/// selection, with the largest selection giving it: `(remainder, value)`.
+/// 
+/// Example: max_k_digits_max_remainder("9431", 2, 5) -> (4, 94)
+#[allow(dead_code)]
+fn max_k_digits_max_remainder(line: &str, k: usize, m: u64) -> Option<(u64, u128)> {
+    max_k_digits_by_remainder(line, k, m)?
+        .into_iter()
+        .enumerate()
+        .rev()
+        .find_map(|(r, value)| value.map(|v| (r as u64, v)))
+}
+
+/// Finds the best max k-digit ordered value over every window of `w` consecutive digits.
+/// 
+/// Each window is solved with `max_k_digits_ordered` and the largest result wins,
+/// so the selected digits all come from one contiguous stretch of the line.
+/// A window wider than the line covers the whole line.
+/// 
+/// Example: max_k_in_windows("9111119", 2, 3) -> 91
+///          (the whole line gives 99, but no 3-wide window holds both 9s)
+/// 
+/// Time: O(n * w), Space: O(w)
+#[allow(dead_code)]
+fn max_k_in_windows(line: &str, k: usize, w: usize) -> Option<u128> {
+    // Non-ASCII lines can't be sliced by byte windows (and aren't digits anyway)
+    if w < k || !line.is_ascii() {
+        return None;
+    }
+    let w = w.min(line.len());
+
+    (0..=line.len().saturating_sub(w))
+        .map(|start| max_k_digits_ordered(&line[start..start + w], k, 10))
+        .collect::<Option<Vec<_>>>()?
+        .into_iter()
+        .max()
+}
+
+/// Sums `max_k_in_windows` over all lines; failing lines contribute 0.
+#[allow(dead_code)]
+fn calculate_total_jolts_windowed<'a, I>(lines: I, k: usize, w: usize) -> u128
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    lines
+        .into_iter()
+        .map(|line| max_k_in_windows(line, k, w).unwrap_or(0))
+        .sum()
 }
 
 /// Calculates the sum of maximum k-digit values across all input lines.
@@ -136,19 +458,161 @@ fn max_k_digits_ordered(line: &str, k: usize) -> Option<u128> {
 /// # Arguments
 /// * `lines` - Iterator of string slices, one per puzzle input line
 /// * `k` - Number of digits to select from each line
+#[allow(dead_code)]
 fn calculate_total_jolts<'a, I>(lines: I, k: usize) -> u128
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    calculate_total_jolts_with(lines, k, DigitPolicy::Strict)
+}
+
+/// `calculate_total_jolts` with non-digit characters handled per `policy`.
+fn calculate_total_jolts_with<'a, I>(lines: I, k: usize, policy: DigitPolicy) -> u128
 where
     I: IntoIterator<Item = &'a str>,
 {
     let mut total_jolts: u128 = 0;
     for line in lines {
         // Extract max k-digit value from this line, default to 0 on failure
-        let jolts = max_k_digits_ordered(line, k).unwrap_or(0) as u128;
+        let jolts = max_k_digits_ordered_with(line, k, 10, policy).unwrap_or(0);
         total_jolts += jolts;
     }
     total_jolts
 }
 
+/// Per-line breakdown of `calculate_total_jolts`.
+/// 
+/// Returns `(line index, max k-digit value)` for every line, with `None` where
+/// the line cannot form k digits. The `Some` values sum to the total.
+#[allow(dead_code)]
+fn jolts_per_line<'a, I>(lines: I, k: usize) -> Vec<(usize, Option<u128>)>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| (i, max_k_digits_ordered(line, k, 10)))
+        .collect()
+}
+
+/// `calculate_total_jolts` accumulated into a `BigUint`.
+/// 
+/// Uses `max_k_digits_ordered_big`, so lines with k above 38 digits contribute
+/// their full value instead of 0, and the sum itself cannot overflow.
+#[allow(dead_code)]
+fn calculate_total_jolts_big<'a, I>(lines: I, k: usize) -> BigUint
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    lines
+        .into_iter()
+        .filter_map(|line| max_k_digits_ordered_big(line, k))
+        .sum()
+}
+
+/// Explains why `max_k_digits_ordered` could not produce a value for `line`.
+fn jolts_failure_reason(line: &str, k: usize) -> String {
+    if k == 0 {
+        return "k must be at least 1".to_string();
+    }
+    if let Some((i, ch)) = line.char_indices().find(|(_, ch)| !ch.is_ascii_digit()) {
+        return format!("invalid digit {ch:?} at position {i}");
+    }
+    if k > line.len() {
+        let plural = if line.len() == 1 { "" } else { "s" };
+        return format!("line has {} digit{plural}, need {k}", line.len());
+    }
+    format!("{k}-digit value overflows u128")
+}
+
+/// Checked variant of `calculate_total_jolts`.
+/// 
+/// Instead of adding 0 for lines that cannot produce a k-digit value, collects
+/// every failing line as `(line_index, reason)`. Returns the total only when
+/// all lines succeed, so a genuine 0 is distinguishable from a parse failure.
+#[allow(dead_code)]
+fn calculate_total_jolts_checked<'a, I>(lines: I, k: usize) -> Result<u128, Vec<(usize, String)>>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut total_jolts: u128 = 0;
+    let mut failures = Vec::new();
+    for (i, line) in lines.into_iter().enumerate() {
+        match max_k_digits_ordered(line, k, 10) {
+            Some(jolts) => total_jolts += jolts,
+            None => failures.push((i, jolts_failure_reason(line, k))),
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(total_jolts)
+    } else {
+        Err(failures)
+    }
+}
+
+/// How `max_line` breaks ties between lines with equal maxima.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[allow(dead_code)]
+enum TiePolicy {
+    /// Keep the earliest line
+    #[default]
+    First,
+    /// Keep the latest line
+    Last,
+    /// Keep the longest line; equal lengths fall back to the earliest
+    Longest,
+}
+
+/// Finds the line whose max k-digit value is largest.
+/// 
+/// Returns `(line index, value)`, or `None` if no line yields a value.
+/// Lines that cannot form k digits are skipped; ties follow `policy`.
+#[allow(dead_code)]
+fn max_line<'a, I>(lines: I, k: usize, policy: TiePolicy) -> Option<(usize, u128)>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut best: Option<(usize, u128, usize)> = None;
+    for (i, line) in lines.into_iter().enumerate() {
+        let Some(value) = max_k_digits_ordered(line, k, 10) else {
+            continue;
+        };
+        let replace = match best {
+            None => true,
+            Some((_, best_value, _)) if value != best_value => value > best_value,
+            Some((_, _, best_len)) => match policy {
+                TiePolicy::First => false,
+                TiePolicy::Last => true,
+                TiePolicy::Longest => line.len() > best_len,
+            },
+        };
+        if replace {
+            best = Some((i, value, line.len()));
+        }
+    }
+    best.map(|(i, value, _)| (i, value))
+}
+
+/// Calculates both the sum of minimum and the sum of maximum k-digit values.
+/// 
+/// Returns `(sum_of_minima, sum_of_maxima)` in a single pass over the lines,
+/// i.e. the range of possible totals. Lines that fail contribute 0 to both.
+#[allow(dead_code)]
+fn total_jolts_minmax<'a, I>(lines: I, k: usize) -> (u128, u128)
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut total_min: u128 = 0;
+    let mut total_max: u128 = 0;
+    for line in lines {
+        total_min += min_k_digits_ordered(line, k).unwrap_or(0);
+        total_max += max_k_digits_ordered(line, k, 10).unwrap_or(0);
+    }
+    (total_min, total_max)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -173,6 +637,17 @@ mod tests {
         assert_eq!(total_jolts, 357);
    }
    
+    /// Test the per-line breakdown on three sample lines plus one that fails.
+    #[test]
+    fn per_line_breakdown_size2() {
+        let lines = ["987654321111111", "811111111111119", "234234234234278", "7"];
+        let per_line = jolts_per_line(lines, 2);
+        assert_eq!(per_line, vec![(0, Some(98)), (1, Some(89)), (2, Some(78)), (3, None)]);
+
+        let total: u128 = per_line.iter().filter_map(|&(_, value)| value).sum();
+        assert_eq!(total, calculate_total_jolts(lines, 2));
+    }
+
     /// Test multiple lines with k=12 (selecting 12 digits from 15-digit strings)
     /// Validates the greedy algorithm works for larger k values
     #[test]
@@ -180,4 +655,228 @@ mod tests {
         let total_jolts = calculate_total_jolts(["987654321111111", "811111111111119", "234234234234278", "818181911112111" ], 12);
         assert_eq!(total_jolts, 3121910778619);
     }
+
+    /// Test hexadecimal digits compared by value, not ASCII ('9' > 'A' in ASCII).
+    /// "9A3F2": the best ordered pair is 'F','2' = 0xF2 = 242 (beats 'A','F' = 175).
+    /// Without the trailing digit, "9A3F" gives 0xAF = 175.
+    #[test]
+    fn hex_radix_selects_by_digit_value() {
+        assert_eq!(max_k_digits_ordered("9A3F2", 2, 16), Some(0xF2));
+        assert_eq!(max_k_digits_ordered("9A3F", 2, 16), Some(175));
+        assert_eq!(max_k_digits_ordered("9a3f", 2, 16), Some(175));
+        assert_eq!(max_k_digits_ordered("9A3F2", 2, 10), None);
+    }
+
+    /// Test the streaming variant on a multi-megabyte synthetic line: it must
+    /// agree with the materialized version while its stack never exceeds k.
+    #[test]
+    fn streaming_matches_ordered_on_long_line() {
+        let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+        let line: String = (0..4_000_000)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                char::from(b'0' + ((state >> 33) % 10) as u8)
+            })
+            .collect();
+
+        for k in [2, 12] {
+            let (value, peak) = max_k_digits_streaming_peak(line.bytes(), line.len(), k, 10).unwrap();
+            assert_eq!(Some(value), max_k_digits_ordered(&line, k, 10));
+            assert!(peak <= k);
+        }
+
+        assert_eq!(max_k_digits_streaming("9A3F".bytes(), 4, 2, 16), Some(175));
+        assert_eq!(max_k_digits_streaming("12".bytes(), 2, 3, 10), None);
+        assert_eq!(max_k_digits_streaming("1x3".bytes(), 3, 2, 10), None);
+    }
+
+    /// Test streaming straight from a `BufRead`, and that a wrong length hint
+    /// is rejected rather than giving a wrong answer.
+    #[test]
+    fn streaming_reads_from_buf_reader() {
+        use std::io::{BufReader, Read};
+
+        let line = "818181911112111";
+        let reader = BufReader::with_capacity(4, line.as_bytes());
+        let bytes = reader.bytes().map_while(Result::ok);
+        assert_eq!(max_k_digits_streaming(bytes, line.len(), 12, 10), max_k_digits_ordered(line, 12, 10));
+
+        assert_eq!(max_k_digits_streaming(line.bytes(), line.len() - 1, 12, 10), None);
+        assert_eq!(max_k_digits_streaming(line.bytes(), line.len() + 1, 12, 10), None);
+    }
+
+    /// Test tie-breaking: lines 0, 2 and 3 all give 98, line 2 is the longest.
+    #[test]
+    fn max_line_tie_policies() {
+        let lines = ["981", "12", "1111918", "98", "55"];
+        assert_eq!(max_line(lines, 2, TiePolicy::default()), Some((0, 98)));
+        assert_eq!(max_line(lines, 2, TiePolicy::First), Some((0, 98)));
+        assert_eq!(max_line(lines, 2, TiePolicy::Last), Some((3, 98)));
+        assert_eq!(max_line(lines, 2, TiePolicy::Longest), Some((2, 98)));
+        assert_eq!(max_line(["1", "x"], 2, TiePolicy::First), None);
+    }
+
+    /// Test that the string selection keeps leading zeros the value drops.
+    #[test]
+    fn selected_digits_keep_leading_zeros() {
+        assert_eq!(max_k_digits_ordered_str("1009", 3).as_deref(), Some("109"));
+        assert_eq!(max_k_digits_ordered_str("0009", 3).as_deref(), Some("009"));
+        assert_eq!(max_k_digits_ordered("0009", 3, 10), Some(9));
+        assert_eq!(max_k_digits_ordered_str("12x", 2), None);
+        assert_eq!(max_k_selection("9A3f", 2, 16).as_deref(), Some("Af"));
+
+        // Numeric results still overflow-check: 40 nines exceed u128
+        assert_eq!(max_k_digits_ordered(&"9".repeat(40), 40, 10), None);
+    }
+
+    /// Test the generic selection on letters, keeping the original order.
+    #[test]
+    fn max_subsequence_of_tokens() {
+        assert_eq!(max_k_subsequence(&['b', 'a', 'c', 'b'], 3), Some(vec!['b', 'c', 'b']));
+        assert_eq!(max_k_subsequence(&['b', 'a', 'c', 'b'], 1), Some(vec!['c']));
+        assert_eq!(max_k_subsequence(&["x", "ab", "b"], 2), Some(vec!["x", "b"]));
+        assert_eq!(max_k_subsequence::<char>(&[], 1), None);
+        assert_eq!(max_k_subsequence(&['a'], 0), None);
+
+        // Digits through the generic core match the string selection
+        let digits: Vec<u8> = "818181911112111".bytes().collect();
+        let picked = String::from_utf8(max_k_subsequence(&digits, 12).unwrap()).unwrap();
+        assert_eq!(Some(picked), max_k_digits_ordered_str("818181911112111", 12));
+    }
+
+    /// Test the minimum selection, where a leading zero survives only in the string.
+    #[test]
+    fn min_selection_size2() {
+        assert_eq!(min_k_digits_ordered("987654321111111", 2), Some(11));
+        assert_eq!(min_k_digits_ordered_str("987654321111111", 2).as_deref(), Some("11"));
+        assert_eq!(min_k_digits_ordered("10234", 2), Some(2));
+        assert_eq!(min_k_digits_ordered_str("10234", 2).as_deref(), Some("02"));
+        assert_eq!(min_k_digits_ordered_str("10234", 6), None);
+        assert_eq!(min_k_digits_ordered_str("1a2", 2), None);
+
+        // 40 digits exceed u128 even though the selection itself is valid
+        assert_eq!(min_k_digits_ordered(&"9".repeat(40), 40), None);
+        assert_eq!(min_k_digits_ordered_str(&"9".repeat(40), 40).map(|s| s.len()), Some(40));
+    }
+
+    /// Test the k argument: default, explicit values, fallback and the 0 error.
+    #[test]
+    fn parse_k_argument() {
+        assert_eq!(parse_k(None), Ok(12));
+        assert_eq!(parse_k(Some("2")), Ok(2));
+        assert_eq!(parse_k(Some("12")), Ok(12));
+        assert_eq!(parse_k(Some("-3")), Err("Invalid k '-3', expected a positive integer".to_string()));
+        assert!(parse_k(Some("0")).is_err());
+        assert!(parse_k(Some("twelve")).is_err());
+    }
+
+    /// Test that separators zero a line in strict mode but are skipped on request.
+    #[test]
+    fn non_digit_policy() {
+        assert_eq!(max_k_digits_ordered_with("98 76 54", 3, 10, DigitPolicy::SkipOthers), Some(987));
+        assert_eq!(max_k_digits_ordered_with("98 76 54", 3, 10, DigitPolicy::Strict), None);
+        assert_eq!(max_k_digits_ordered_with("1-2,3 ", 3, 10, DigitPolicy::SkipOthers), Some(123));
+        // Filtering can leave fewer than k digits
+        assert_eq!(max_k_digits_ordered_with("1 2", 3, 10, DigitPolicy::SkipOthers), None);
+        assert_eq!(max_k_digits_ordered_with("9A 3F", 2, 16, DigitPolicy::SkipOthers), Some(0xAF));
+
+        let lines = ["987654321111111 ", "81111111111111 9"];
+        assert_eq!(calculate_total_jolts_with(lines, 2, DigitPolicy::Strict), 0);
+        assert_eq!(calculate_total_jolts(lines, 2), 0);
+        assert_eq!(calculate_total_jolts_with(lines, 2, DigitPolicy::SkipOthers), 98 + 89);
+    }
+
+    /// Test k=40 on a 50-digit line, past what u128 can hold.
+    #[test]
+    fn big_selection_beyond_u128() {
+        let line = "31415926535897932384626433832795028841971693993751";
+        let expected = BigUint::parse_bytes(b"9897932384626433832795028841971693993751", 10);
+        assert_eq!(max_k_digits_ordered(line, 40, 10), None);
+        assert_eq!(max_k_digits_ordered_big(line, 40), expected);
+
+        // Small k agrees with the u128 path, and the big total counts what u128 drops
+        assert_eq!(max_k_digits_ordered_big(line, 12), max_k_digits_ordered(line, 12, 10).map(BigUint::from));
+        assert_eq!(calculate_total_jolts([line, "12"], 40), 0);
+        assert_eq!(calculate_total_jolts_big([line, "12"], 40), expected.unwrap());
+        assert_eq!(
+            calculate_total_jolts_big(["987654321111111", "811111111111119"], 2),
+            BigUint::from(calculate_total_jolts(["987654321111111", "811111111111119"], 2))
+        );
+    }
+
+    /// Test the divisibility DP where the greedy maximum (94) is not divisible.
+    #[test]
+    fn divisible_selection_beats_greedy() {
+        assert_eq!(max_k_digits_ordered("9431", 2, 10), Some(94));
+        assert_eq!(max_k_digits_divisible("9431", 2, 3), Some(93));
+        assert_eq!(max_k_digits_divisible("9431", 2, 7), Some(91));
+        assert_eq!(max_k_digits_divisible("9431", 2, 1), Some(94));
+        assert_eq!(max_k_digits_divisible("11", 2, 2), None);
+        assert_eq!(max_k_digits_divisible("9431", 5, 3), None);
+        assert_eq!(max_k_digits_max_remainder("9431", 2, 5), Some((4, 94)));
+        assert_eq!(max_k_digits_max_remainder("9431", 2, 3), Some((2, 41)));
+
+        // The modulus sizes the DP table, so 0 and oversized values are rejected
+        assert_eq!(max_k_digits_divisible("9431", 2, 0), None);
+        assert_eq!(max_k_digits_max_remainder("9431", 2, MAX_REMAINDER_MODULUS), Some((94, 94)));
+        assert_eq!(max_k_digits_by_remainder("9431", 2, MAX_REMAINDER_MODULUS + 1), None);
+        assert_eq!(max_k_digits_by_remainder("9431", 2, u64::MAX), None);
+
+        // Cross-check against brute force over all 3-digit selections
+        let line = "8273645190";
+        for m in 1..=13 {
+            let mut brute: Option<u128> = None;
+            for a in 0..line.len() {
+                for b in a + 1..line.len() {
+                    for c in b + 1..line.len() {
+                        let digits = [a, b, c].map(|i| (line.as_bytes()[i] - b'0') as u128);
+                        let v = digits[0] * 100 + digits[1] * 10 + digits[2];
+                        if v % m as u128 == 0 {
+                            brute = brute.max(Some(v));
+                        }
+                    }
+                }
+            }
+            assert_eq!(max_k_digits_divisible(line, 3, m), brute, "m = {m}");
+        }
+    }
+
+    /// Test that windows restrict the selection: the two 9s in "9111119" are too far
+    /// apart for a 3-wide window, so the best is 91 instead of the whole-line 99.
+    #[test]
+    fn windowed_max_restricts_to_substrings() {
+        assert_eq!(max_k_digits_ordered("9111119", 2, 10), Some(99));
+        assert_eq!(max_k_in_windows("9111119", 2, 3), Some(91));
+        assert_eq!(max_k_in_windows("9111119", 2, 7), Some(99));
+        assert_eq!(max_k_in_windows("9111119", 2, 100), Some(99));
+        assert_eq!(max_k_in_windows("9111119", 3, 2), None);
+        assert_eq!(max_k_in_windows("91x1119", 2, 3), None);
+        assert_eq!(calculate_total_jolts_windowed(["9111119", "811118"], 2, 3), 91 + 81);
+    }
+
+    /// Test that failing lines are reported with their index and reason,
+    /// while all-valid input still yields the plain total.
+    #[test]
+    fn checked_total_reports_failing_lines() {
+        let result = calculate_total_jolts_checked(["987654321111111", "12a4", "7", "811111111111119"], 2);
+        assert_eq!(
+            result,
+            Err(vec![
+                (1, "invalid digit 'a' at position 2".to_string()),
+                (2, "line has 1 digit, need 2".to_string()),
+            ])
+        );
+
+        assert_eq!(calculate_total_jolts_checked(["987654321111111", "811111111111119"], 2), Ok(98 + 89));
+        assert_eq!(jolts_failure_reason("12", 3), "line has 2 digits, need 3");
+    }
+
+    /// Test both extremes with k=2 on the sample:
+    /// Minima: 11 + 11 + 22 + 11 = 55
+    /// Maxima: 98 + 89 + 78 + 92 = 357
+    #[test]
+    fn minmax_totals_size2() {
+        let lines = ["987654321111111", "811111111111119", "234234234234278", "818181911112111"];
+        assert_eq!(total_jolts_minmax(lines, 2), (55, 357));
+    }
 }
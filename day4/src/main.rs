@@ -1,106 +1,399 @@
 use std::env;
-use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::ops::Range;
 
-const INPUT_URL: &str = "https://adventofcode.com/2025/day/4/input";
+#[path = "../../tools/aoc_io.rs"]
+mod aoc_io;
+use aoc_io::with_input;
 
 /// All 8 neighbor directions as (dr, dc):
 ///   (-1,-1) (-1,0) (-1,1)
 ///   ( 0,-1)        ( 0,1)
 ///   ( 1,-1) ( 1,0) ( 1,1)
-
 const NEIGHBORS: &[(isize, isize)] = &[
     (-1, -1), (-1, 0), (-1, 1),
     ( 0, -1),          ( 0, 1),
     ( 1, -1), ( 1, 0), ( 1, 1)
 ];
 
-type Grid = Vec<Vec<char>>;
+/// The 4 orthogonal directions, the subset of `NEIGHBORS` sharing an edge.
+const ORTHOGONAL: &[(isize, isize)] = &[
+              (-1, 0),
+    ( 0, -1),          ( 0, 1),
+              ( 1, 0),
+];
+
+/// Which cells count as adjacent to a roll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Connectivity {
+    /// Orthogonal neighbors only (`ORTHOGONAL`)
+    Four,
+    /// Orthogonal and diagonal neighbors (`NEIGHBORS`, the puzzle's rule)
+    #[default]
+    Eight,
+}
+
+impl Connectivity {
+    /// The neighbor directions as (dr, dc).
+    fn neighbors(self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => ORTHOGONAL,
+            Connectivity::Eight => NEIGHBORS,
+        }
+    }
+}
+
+/// A grid cell symbol: the processors only need to tell rolls apart and
+/// write the empty and removed markers, so they work on `char` and `u8` grids alike.
+trait Cell: Copy + PartialEq {
+    const ROLL: Self;
+    const EMPTY: Self;
+    const REMOVED: Self;
+}
+
+impl Cell for char {
+    const ROLL: Self = '@';
+    const EMPTY: Self = '.';
+    const REMOVED: Self = 'x';
+}
+
+impl Cell for u8 {
+    const ROLL: Self = b'@';
+    const EMPTY: Self = b'.';
+    const REMOVED: Self = b'x';
+}
+
+type CellGrid<C> = Vec<Vec<C>>;
+
+type Grid = CellGrid<char>;
+
+/// Byte-based grid, indexed without any UTF-8 decoding (as in day7).
+#[allow(dead_code)]
+type ByteGrid = CellGrid<u8>;
+
+/// A roll is removed while it has fewer than this many neighboring rolls.
+const REMOVAL_THRESHOLD: u8 = 4;
+
+/// Puzzle rules shared by the single and multi-pass processors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rules {
+    /// A roll is accessible (removed) while it has fewer neighboring rolls than this
+    threshold: u8,
+    /// Which cells count as neighbors, for both the counts and the cascade updates
+    connectivity: Connectivity,
+    /// Toroidal grid: neighbors past an edge wrap to the opposite edge instead
+    /// of being skipped
+    wrap: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules { threshold: REMOVAL_THRESHOLD, connectivity: Connectivity::Eight, wrap: false }
+    }
+}
+
+/// Per-cell removal timing: `Some(pass)` (1-indexed) if the roll was removed
+/// on that pass of the multi-pass cascade, `None` if it survived or was empty.
+type PassGrid = Vec<Vec<Option<usize>>>;
 
 #[derive(Debug, Clone)]
-struct Stats {
-    out: Grid,
+struct Stats<C = char> {
+    out: CellGrid<C>,
     passes : usize, // how many "waves" happened until no more rolls are accessible
     total_removed: usize, // how many rolls were removed in total
+    original_rolls: usize, // how many rolls the input grid had
+    /// Rolls removed in each multi-pass wave (sums to `total_removed`, one entry
+    /// per pass); empty for a single pass, which counts 0 passes
+    removed_per_pass: Vec<usize>,
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+impl<C> Stats<C> {
+    /// Fraction of the original rolls that were removed (0.0 for a grid without rolls).
+    fn removed_fraction(&self) -> f64 {
+        if self.original_rolls == 0 {
+            return 0.0;
+        }
+        self.total_removed as f64 / self.original_rolls as f64
+    }
+}
 
-    // Mode selection: single or multi pass (default: single)
-   let mode = env::args().nth(1).unwrap_or_else(|| "single".to_string());
+/// Rectangular subgrid (half-open row and column ranges) that removal is
+/// confined to. Cells outside still count as neighbors but are never removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Region {
+    rows: Range<usize>,
+    cols: Range<usize>,
+}
+
+impl Region {
+    fn contains(&self, r: usize, c: usize) -> bool {
+        self.rows.contains(&r) && self.cols.contains(&c)
+    }
+}
+
+/// Whether `(r, c)` may be removed: everywhere without a region, else inside it.
+fn in_region(region: Option<&Region>, r: usize, c: usize) -> bool {
+    region.is_none_or(|region| region.contains(r, c))
+}
+
+/// Which multi-pass removal counts get printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PassLog {
+    /// Print nothing
+    Silent,
+    /// Print every pass
+    All,
+    /// Print only the first and the last pass
+    FirstLast,
+    /// Print every Nth pass (N, 2N, ...), plus the last one
+    EveryNth(usize),
+}
+
+impl PassLog {
+    /// Parses `none`, `all`, `first-last` or a number N (every Nth pass).
+    fn parse(arg: &str) -> Result<PassLog, String> {
+        match arg {
+            "none" => Ok(PassLog::Silent),
+            "all" => Ok(PassLog::All),
+            "first-last" => Ok(PassLog::FirstLast),
+            n => match n.parse::<usize>() {
+                Ok(n) if n > 0 => Ok(PassLog::EveryNth(n)),
+                _ => Err(format!("Invalid pass log '{arg}'. Use none, all, first-last or N > 0")),
+            },
+        }
+    }
+
+    /// Whether pass `pass` (1-indexed) out of `total` passes is printed.
+    fn shows(&self, pass: usize, total: usize) -> bool {
+        match *self {
+            PassLog::Silent => false,
+            PassLog::All => true,
+            PassLog::FirstLast => pass == 1 || pass == total,
+            PassLog::EveryNth(n) => pass.is_multiple_of(n) || pass == total,
+        }
+    }
+}
 
-    let session = env::var("AOC_SESSION")
-        .map_err(|_| "AOC_SESSION environment variable is not set")?;
+fn main() -> Result<(), Box<dyn std::error::Error>> {
 
-    let body = ureq::get(INPUT_URL)
-        .header("Cookie", &format!("session={session}"))
-        .call()?
-        .into_body()
-        .read_to_string()?;
+    // Mode selection: single or multi pass (default: single)
+    // `--raw` prints only the bare total removed (no grids or labels)
+    // `--passes=<none|all|first-last|N>` selects which multi-pass counts are printed
+    // `--strict` rejects cells other than '@', '.' and 'x'
+    // `--threshold=N` removes rolls with fewer than N neighbors (default 4)
+    // `--connectivity=4` counts orthogonal neighbors only (default 8)
+    // `--wrap` treats the grid as a torus (edges neighbor the opposite edges)
+    // `--min-len=N` skips input lines shorter than N cells (e.g. a truncated last line)
+    let args: Vec<String> = env::args().skip(1).collect();
+    let raw = args.iter().any(|arg| arg == "--raw");
+    let strict = args.iter().any(|arg| arg == "--strict");
+    let pass_log = match args.iter().find_map(|arg| arg.strip_prefix("--passes=")) {
+        Some(value) => PassLog::parse(value)?,
+        None => PassLog::All,
+    };
+    let mut rules = Rules { wrap: args.iter().any(|arg| arg == "--wrap"), ..Rules::default() };
+    if let Some(value) = args.iter().find_map(|arg| arg.strip_prefix("--threshold=")) {
+        rules.threshold = value.parse().map_err(|_| format!("Invalid threshold '{value}'"))?;
+    }
+    if let Some(value) = args.iter().find_map(|arg| arg.strip_prefix("--connectivity=")) {
+        rules.connectivity = match value {
+            "4" => Connectivity::Four,
+            "8" => Connectivity::Eight,
+            _ => return Err(format!("Invalid connectivity '{value}'. Use 4 or 8").into()),
+        };
+    }
+    let min_len = match args.iter().find_map(|arg| arg.strip_prefix("--min-len=")) {
+        Some(value) => value.parse().map_err(|_| format!("Invalid min length '{value}'"))?,
+        None => 0,
+    };
+    let mode = args
+        .iter()
+        .find(|arg| !arg.starts_with("--"))
+        .cloned()
+        .unwrap_or_else(|| "single".to_string());
+
+    with_input(4, |body| {
+        let grid = process_input_grid_checked(body, strict, min_len)?;
+        write_run(&mut io::stdout().lock(), &grid, &mode, rules, pass_log, raw)
+    })
+}
 
-    let grid = process_input_grid(&body);
+/// Runs `mode` ("single" or "multi") on `grid` and writes the report to `out`.
+/// 
+/// In raw mode only the bare total removed is written; otherwise the original
+/// grid, the pass log (multi only), the final grid and the totals.
+fn write_run(
+    out: &mut impl Write,
+    grid: &Grid,
+    mode: &str,
+    rules: Rules,
+    pass_log: PassLog,
+    raw: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if raw {
+        let stats = match mode {
+            "single" => process_grid_single_with(grid, rules),
+            "multi" => process_grid_multi_with(grid, rules),
+            _ => return Err(Box::from(format!("Unknown mode: '{}'", mode))),
+        };
+        writeln!(out, "{}", stats.total_removed)?;
+        return Ok(());
+    }
 
-    println!("=== Original grid ===");
-    print_grid(&grid);
-    println!();
+    writeln!(out, "=== Original grid ===")?;
+    write_grid(out, grid)?;
+    writeln!(out)?;
 
-    match mode.as_str() {
+    match mode {
         "single" => {
-            println!("Running SINGLE pass marking ...");
-            let stats = process_grid_single(&grid);
-            println!("\n Final Grid with removed accessible rolls");
-            print_grid(&stats.out);
-            println!("\nSINGLE: total removed = {}", stats.total_removed);
+            writeln!(out, "Running SINGLE pass marking ...")?;
+            let stats = process_grid_single_with(grid, rules);
+            writeln!(out, "\n Final Grid with removed accessible rolls")?;
+            write_grid(out, &stats.out)?;
+            writeln!(out, "\nSINGLE: total removed = {} ({:.1}% of rolls)", stats.total_removed, stats.removed_fraction() * 100.0)?;
             Ok(())
         }
         "multi" => {
-            println!("Running MULTI pass ...");
-            let stats = process_grid_multi(&grid);
-            println!("\n Final Grid with removed accessible rolls");
-            print_grid(&stats.out);
-            println!("\nMULTI: passes = {}, total removed = {}",stats.passes, stats.total_removed);
+            writeln!(out, "Running MULTI pass ...")?;
+            let stats = process_grid_multi_with(grid, rules);
+            for (i, &removed) in stats.removed_per_pass.iter().enumerate() {
+                if pass_log.shows(i + 1, stats.passes) {
+                    writeln!(out, "Pass {}: removed {removed} rolls", i + 1)?;
+                }
+            }
+            writeln!(out, "\n Final Grid with removed accessible rolls")?;
+            write_grid(out, &stats.out)?;
+            writeln!(out, "\nMULTI: passes = {}, total removed = {} ({:.1}% of rolls)",stats.passes, stats.total_removed, stats.removed_fraction() * 100.0)?;
             Ok(())
         }
         _ => {
             Err(Box::from(format!("Unknown mode: '{}'", mode)))
         }
     }
-
 }
 
+#[allow(dead_code)]
 fn process_input_grid(s: &str) -> Grid {
 
     s.lines().map(|line| line.chars().collect()).collect()
     
 }
 
-fn print_grid(grid: &Grid) {
+/// Byte counterpart of `process_input_grid`: splits on '\n' (dropping a
+/// trailing '\r') and keeps every byte as a cell.
+#[allow(dead_code)]
+fn from_bytes(input: &[u8]) -> ByteGrid {
+    let mut lines: Vec<&[u8]> = input.split(|&b| b == b'\n').collect();
+    if lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+        .into_iter()
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line).to_vec())
+        .collect()
+}
+
+/// Glyphs the grid format knows about: roll, empty, removed roll.
+const KNOWN_CELLS: &[char] = &['@', '.', 'x'];
+
+/// Parses the grid, optionally rejecting unknown glyphs.
+///
+/// With `strict` unset cells parse as in `process_input_grid`: anything other
+/// than '@' behaves as empty. With `strict` set, the first cell outside `KNOWN_CELLS`
+/// is reported by its 1-based line and column.
+/// 
+/// Lines shorter than `min_len` cells are skipped (0 keeps every line), so a
+/// stray partial line doesn't become a row; the rows that remain must all be
+/// as wide as the first one.
+fn process_input_grid_checked(s: &str, strict: bool, min_len: usize) -> Result<Grid, String> {
+    let mut grid: Grid = Vec::new();
+    for (r, line) in s.lines().enumerate() {
+        let row: Vec<char> = line.chars().collect();
+        if row.len() < min_len {
+            continue;
+        }
+        if strict && let Some(c) = row.iter().position(|ch| !KNOWN_CELLS.contains(ch)) {
+            return Err(format!(
+                "Unexpected cell '{}' at line {}, column {}",
+                row[c],
+                r + 1,
+                c + 1
+            ));
+        }
+        if let Some(first) = grid.first() && row.len() != first.len() {
+            return Err(format!(
+                "Ragged grid: line {} has {} cells, expected {}",
+                r + 1,
+                row.len(),
+                first.len()
+            ));
+        }
+        grid.push(row);
+    }
+    Ok(grid)
+}
+
+/// Trims border rows/columns that contain no rolls.
+/// 
+/// Returns the cropped grid and the `(row, col)` offset of its top-left cell in
+/// the original grid, so `(r, c)` in the crop is `(r + row, c + col)` originally.
+/// Removal results are unaffected since out-of-bounds neighbors already count
+/// as empty. A grid without any rolls crops to an empty grid at offset (0, 0).
+#[allow(dead_code)]
+fn crop_to_content(grid: &Grid) -> (Grid, (usize, usize)) {
+    let has_roll = |row: &Vec<char>| row.contains(&'@');
+
+    let Some(top) = grid.iter().position(has_roll) else {
+        return (Grid::new(), (0, 0));
+    };
+    let bottom = grid.iter().rposition(has_roll).unwrap();
+
+    let rows = &grid[top..=bottom];
+    let left = rows
+        .iter()
+        .filter_map(|row| row.iter().position(|&c| c == '@'))
+        .min()
+        .unwrap();
+    let right = rows
+        .iter()
+        .filter_map(|row| row.iter().rposition(|&c| c == '@'))
+        .max()
+        .unwrap();
+
+    let cropped = rows.iter().map(|row| row[left..=right].to_vec()).collect();
+    (cropped, (top, left))
+}
+
+fn write_grid(out: &mut impl Write, grid: &Grid) -> io::Result<()> {
     for row in grid {
         let line: String = row.iter().collect();
-        println!("{line}")
+        writeln!(out, "{line}")?;
     }
+    Ok(())
 }
 
-fn count_adjacent_rolls(grid : &Grid, r: usize, c: usize) -> u8 {
-    
-    let rows = grid.len() as isize;
-    let cols = grid[0].len() as isize;
+fn count_adjacent_rolls<C: Cell>(grid : &CellGrid<C>, r: usize, c: usize) -> u8 {
+    count_adjacent_rolls_with(grid, r, c, Rules::default())
+}
 
-    let r = r as isize;
-    let c = c as isize;
+/// `count_adjacent_rolls` over the neighbor set of `rules.connectivity`,
+/// wrapping around the edges when `rules.wrap` is set.
+/// 
+/// Neighbors are counted per direction, so on a torus narrower than 3 cells
+/// the same roll (or the cell itself) can be counted more than once.
+fn count_adjacent_rolls_with<C: Cell>(grid : &CellGrid<C>, r: usize, c: usize, rules: Rules) -> u8 {
+    
+    let rows = grid.len();
+    let cols = grid[0].len();
 
     let mut adj_rolls = 0u8;
 
-    for (dr, dc) in NEIGHBORS {
-        let nr = r + dr;
-        let nc = c + dc;
-
-        if nr < 0 || nr >= rows || nc < 0 || nc >= cols {
+    for &dir in rules.connectivity.neighbors() {
+        let Some((ur, uc)) = offset_cell(rows, cols, r, c, dir, rules.wrap) else {
             continue;
-        }
+        };
 
-        let (ur, uc) = (nr as usize, nc as usize);
-
-        if grid[ur][uc] == '@' {
+        if grid[ur][uc] == C::ROLL {
             adj_rolls += 1;
         }
     }
@@ -108,34 +401,147 @@ fn count_adjacent_rolls(grid : &Grid, r: usize, c: usize) -> u8 {
     adj_rolls
 }
 
-fn process_grid_single(grid: &Grid) -> Stats {
+/// The cell `dir` = (dr, dc) away from `(r, c)` in a `rows` x `cols` grid.
+/// 
+/// `None` past an edge, unless `wrap` is set: then the coordinates wrap
+/// around to the opposite edge.
+fn offset_cell(rows: usize, cols: usize, r: usize, c: usize, dir: (isize, isize), wrap: bool) -> Option<(usize, usize)> {
+    let nr = r as isize + dir.0;
+    let nc = c as isize + dir.1;
+
+    if wrap {
+        return Some((nr.rem_euclid(rows as isize) as usize, nc.rem_euclid(cols as isize) as usize));
+    }
+    if nr < 0 || nr >= rows as isize || nc < 0 || nc >= cols as isize {
+        return None;
+    }
+    Some((nr as usize, nc as usize))
+}
+
+/// Counts how many rolls have 0..=8 adjacent rolls.
+/// 
+/// `hist[n]` is the number of '@' cells with exactly `n` neighboring rolls.
+/// Everything below the removal threshold is what a single pass will remove.
+#[allow(dead_code)]
+fn adjacency_histogram(grid: &Grid) -> [u64; 9] {
+    let mut hist = [0u64; 9];
+
+    for (r, row) in grid.iter().enumerate() {
+        for (c, &cell) in row.iter().enumerate() {
+            if cell == '@' {
+                hist[count_adjacent_rolls(grid, r, c) as usize] += 1;
+            }
+        }
+    }
+
+    hist
+}
+
+/// "What-if" for adding rolls: empty cells where a new roll would make a
+/// neighboring roll stable.
+/// 
+/// A roll is stable once it has `rules.threshold` or more neighboring rolls,
+/// so an empty '.' cell is a candidate when at least one adjacent roll
+/// currently has exactly `threshold - 1`. Adjacency (and wrapping) follows
+/// `rules`, as in the processors. Candidates are returned in row-major order.
+#[allow(dead_code)]
+fn candidate_additions(grid: &Grid, rules: Rules) -> Vec<(usize, usize)> {
+    let rows = grid.len();
+    let cols = grid.first().map_or(0, Vec::len);
+    let mut candidates = Vec::new();
+
+    for r in 0..rows {
+        for c in 0..cols {
+            if grid[r][c] != '.' {
+                continue;
+            }
+            let tips_a_neighbor = rules.connectivity.neighbors().iter().any(|&dir| {
+                let Some((ur, uc)) = offset_cell(rows, cols, r, c, dir, rules.wrap) else {
+                    return false;
+                };
+                grid[ur][uc] == '@' && count_adjacent_rolls_with(grid, ur, uc, rules) + 1 == rules.threshold
+            });
+            if tips_a_neighbor {
+                candidates.push((r, c));
+            }
+        }
+    }
+
+    candidates
+}
+
+/// Cells the multi-pass cascade removes but a single pass does not.
+/// 
+/// Runs both processors with the same `threshold` and diffs their removed
+/// sets; a single pass only removes what is already below the threshold, so
+/// the result is exactly the cascade-only removals, in row-major order.
+#[allow(dead_code)]
+fn single_multi_diff(grid: &Grid, threshold: u8) -> Vec<(usize, usize)> {
+    let rules = Rules { threshold, ..Rules::default() };
+    let single = process_grid_single_in(grid, None, rules);
+    let (multi, _) = process_grid_multi_flat_in(grid, None, rules, |_, _| {});
+
+    let mut diff = Vec::new();
+    for (r, (multi_row, single_row)) in multi.out.iter().zip(&single.out).enumerate() {
+        for (c, (&m, &s)) in multi_row.iter().zip(single_row).enumerate() {
+            if m == 'x' && s != 'x' {
+                diff.push((r, c));
+            }
+        }
+    }
+    diff
+}
+
+#[allow(dead_code)]
+fn process_grid_single<C: Cell>(grid: &CellGrid<C>) -> Stats<C> {
+    process_grid_single_with(grid, Rules::default())
+}
+
+/// `process_grid_single` under `rules` instead of the default threshold of 4.
+fn process_grid_single_with<C: Cell>(grid: &CellGrid<C>, rules: Rules) -> Stats<C> {
+    process_grid_single_in(grid, None, rules)
+}
+
+/// Single pass limited to `region`.
+/// 
+/// Rolls outside the region are copied through unchanged and are not part of
+/// `original_rolls`; rolls inside see the full grid when counting neighbors,
+/// so cells on the region's border behave as they would in a full run.
+fn process_grid_single_in<C: Cell>(grid: &CellGrid<C>, region: Option<&Region>, rules: Rules) -> Stats<C> {
 
     let mut total_removed: usize = 0;
+    let mut original_rolls: usize = 0;
     let passes = 0usize;
 
-    let rows = grid.len() as usize;
-    let cols = grid[0].len() as usize;
+    // An empty grid (e.g. every line filtered by `--min-len`) has no columns
+    let rows = grid.len();
+    let cols = grid.first().map_or(0, Vec::len);
 
     // This will hold a marking of accessibility:
     // 'x' = accessible '@'
     // '@' = non-accessible '@'
     // '.' = empty
 
-    let mut out: Grid = vec![vec!['.'; cols as usize]; rows as usize];
+    let mut out: CellGrid<C> = vec![vec![C::EMPTY; cols]; rows];
 
     for r in 0..rows {
         for c in 0..cols {
-            if grid[r][c] != '@' {
+            if grid[r][c] != C::ROLL {
                 continue;
             }
+            if !in_region(region, r, c) {
+                out[r][c] = C::ROLL;
+                continue;
+            }
+            original_rolls += 1;
 
-            let adj_rolls = count_adjacent_rolls(&grid, r, c);
+            let adj_rolls = count_adjacent_rolls_with(grid, r, c, rules);
 
-            if adj_rolls < 4 {
-                out[r][c] = 'x';
+            if adj_rolls < rules.threshold {
+                out[r][c] = C::REMOVED;
                 total_removed += 1;
             } else {
-                out[r][c] = '@';
+                out[r][c] = C::ROLL;
             }
         }
     }
@@ -143,101 +549,289 @@ fn process_grid_single(grid: &Grid) -> Stats {
     Stats {
         out,
         passes,
-        total_removed
+        total_removed,
+        original_rolls,
+        removed_per_pass: Vec::new(),
     }
 }
 
-fn process_grid_multi(grid: &Grid) -> Stats {
+#[allow(dead_code)]
+fn process_grid_multi<C: Cell>(grid: &CellGrid<C>) -> Stats<C> {
+    process_grid_multi_with(grid, Rules::default())
+}
+
+/// `process_grid_multi` under `rules` instead of the default threshold of 4.
+fn process_grid_multi_with<C: Cell>(grid: &CellGrid<C>, rules: Rules) -> Stats<C> {
+    process_grid_multi_flat_in(grid, None, rules, |_, _| {}).0
+}
+
+/// Returns the pass number each cell was removed in (a removal-timing heatmap).
+#[allow(dead_code)]
+fn removal_passes(grid: &Grid) -> PassGrid {
+    process_grid_multi_timed(grid, |_, _| {}).1
+}
+
+/// Multi-pass cascade that also records, per cell, the pass it was removed in.
+/// 
+/// `on_pass(pass, removed)` is called after each pass (1-indexed) with the number
+/// of rolls removed in it; reporting lives there, outside the core loop.
+fn process_grid_multi_timed<C: Cell>(grid: &CellGrid<C>, on_pass: impl FnMut(usize, usize)) -> (Stats<C>, PassGrid) {
+    process_grid_multi_timed_in(grid, None, Rules::default(), on_pass)
+}
+
+/// Multi-pass cascade limited to `region`.
+#[allow(dead_code)]
+fn process_grid_multi_in<C: Cell>(grid: &CellGrid<C>, region: Option<&Region>) -> Stats<C> {
+    process_grid_multi_flat_in(grid, region, Rules::default(), |_, _| {}).0
+}
+
+/// `process_grid_multi_timed` with removal confined to `region` (see `process_grid_single_in`).
+/// 
+/// Cells already marked `C::REMOVED` in the input count as gone: they add
+/// nothing to the initial degrees and are never queued, so feeding back a
+/// partial `out` resumes the cascade from that state. Passes and
+/// `original_rolls` are then counted from the resumed state.
+fn process_grid_multi_timed_in<C: Cell>(
+    grid: &CellGrid<C>,
+    region: Option<&Region>,
+    rules: Rules,
+    on_pass: impl FnMut(usize, usize),
+) -> (Stats<C>, PassGrid) {
+    let (stats, stamps) = process_grid_multi_flat_in(grid, region, rules, on_pass);
+    let cols = grid.first().map_or(0, Vec::len);
+    let removed_in = (0..grid.len())
+        .map(|r| (0..cols).map(|c| Some(stamps[r * cols + c] as usize).filter(|&pass| pass > 0)).collect())
+        .collect();
+
+    (stats, removed_in)
+}
+
+/// The cascade behind `process_grid_multi_timed_in`, on flat buffers.
+/// 
+/// Works on flat indices (`r * cols + c`): the working grid is one `Vec<C>`,
+/// each wave is a plain `Vec` and the next one is filled while the current is
+/// drained. `stamps[i]` holds the wave that cell `i` was scheduled for, so
+/// "already queued" is a stamp comparison and no per-wave bool grid has to be
+/// allocated or cleared. A queued roll is always removed in the wave it was
+/// queued for, so the stamps double as the per-cell removal passes (0 = never
+/// removed) and are returned as they are; only callers that want a `PassGrid`
+/// pay for building one.
+fn process_grid_multi_flat_in<C: Cell>(
+    grid: &CellGrid<C>,
+    region: Option<&Region>,
+    rules: Rules,
+    mut on_pass: impl FnMut(usize, usize),
+) -> (Stats<C>, Vec<u32>) {
     
-    let mut out = grid.clone();
     let mut total_removed = 0usize;
-    let mut passes = 0usize;
+    let mut passes = 0u32;
+    let mut original_rolls = 0usize;
+    let mut removed_per_pass = Vec::new();
     
-    let rows = out.len();
-    let cols = out[0].len();
+    let rows = grid.len();
+    let cols = grid.first().map_or(0, Vec::len);
+    let mut out: Vec<C> = grid.iter().flatten().copied().collect();
 
-    // degree[r][c] = how many rolls neighbors cell (r,c) currently has
-    let mut degree =  vec![vec![0u8; cols]; rows];
+    // degree[i] = how many rolls neighbors cell i currently has
+    let mut degree = vec![0u8; rows * cols];
 
-    // 1) compute initial degrees using the shared count_adjacent_rolls
+    // stamps[i] = wave cell i was scheduled for, and so removed in (0 = never)
+    let mut stamps = vec![0u32; rows * cols];
 
-    for r in 0..rows {
-        for c in 0..cols {
-            if out[r][c] == '@' {
-                degree[r][c] = count_adjacent_rolls(&out, r, c);
-            }
-        }
-    }
+    // 1) compute initial degrees using the shared count_adjacent_rolls_with
+    //    (only ROLL neighbors count, so pre-removed cells are excluded)
+    // 2) first wave: all cells with '@' and degree < rules.threshold
+    let mut wave: Vec<usize> = Vec::new();
 
-    // 2) initial queue: all cells with '@' and degree < 4
-    let mut queue = VecDeque::new();
-    let mut in_queue = vec![vec![false; cols]; rows];
-    
     for r in 0..rows {
         for c in 0..cols {
-            if out[r][c] == '@' && degree[r][c] < 4 {
-                queue.push_back((r,c));
-                in_queue[r][c] = true;
+            let i = r * cols + c;
+            if out[i] == C::ROLL && in_region(region, r, c) {
+                original_rolls += 1;
+                degree[i] = count_adjacent_rolls_with(grid, r, c, rules);
+                if degree[i] < rules.threshold {
+                    wave.push(i);
+                    stamps[i] = 1;
+                }
             }
         }
     }
 
     // 3) process in passes
-    while !queue.is_empty() {
+    let mut next: Vec<usize> = Vec::with_capacity(wave.len());
+    while !wave.is_empty() {
         passes += 1;
         let mut removed_this_wave = 0usize;
 
-        let layer_size = queue.len();
-        for _ in 0..layer_size {
-            let (r,c) = queue.pop_front().unwrap();
-            in_queue[r][c] = false;
-
-            if out[r][c] != '@' {
+        for &i in &wave {
+            if out[i] != C::ROLL {
                 continue; // it might have been removed already
             }
 
             // remove this roll
-            out[r][c] = 'x';
+            out[i] = C::REMOVED;
             total_removed += 1;
             removed_this_wave += 1;
 
             // update neighbors' degrees
-            for (dr,dc) in NEIGHBORS {
-                let nr = r as isize + dr;
-                let nc = c as isize + dc;
-
-                if nr < 0 || nr >= rows as isize || nc < 0 || nc >= cols as isize {
+            let (r, c) = (i / cols, i % cols);
+            for &dir in rules.connectivity.neighbors() {
+                let Some((ur, uc)) = offset_cell(rows, cols, r, c, dir, rules.wrap) else {
                     continue;
-                }
-                let (ur, uc) = (nr as usize, nc as usize);
+                };
 
-                if out[ur][uc] != '@' {
+                let j = ur * cols + uc;
+                if out[j] != C::ROLL || !in_region(region, ur, uc) {
                     continue;
                 }
 
-                if degree[ur][uc] > 0 {
-                    degree[ur][uc] -= 1;
+                if degree[j] > 0 {
+                    degree[j] -= 1;
                 }
 
-                if degree[ur][uc] < 4 && !in_queue[ur][uc] {
-                    queue.push_back((ur, uc));
-                    in_queue[ur][uc] = true;
+                // Stamped with this wave (not yet reached) or the next: already queued
+                if degree[j] < rules.threshold && stamps[j] < passes {
+                    next.push(j);
+                    stamps[j] = passes + 1;
                 }
             }
         }
-        println!("Pass {passes}: removed {removed_this_wave} rolls");
+        removed_per_pass.push(removed_this_wave);
+        on_pass(passes as usize, removed_this_wave);
+
+        wave.clear();
+        std::mem::swap(&mut wave, &mut next);
     }
 
-    Stats {
+    // Back to rows only once, for the caller; a grid without columns has nothing to split
+    let out = if cols == 0 { vec![Vec::new(); rows] } else { out.chunks(cols).map(<[C]>::to_vec).collect() };
+
+    let stats = Stats {
             out,
-            passes,
-            total_removed
-    }
+            passes: passes as usize,
+            total_removed,
+            original_rolls,
+            removed_per_pass,
+    };
+
+    (stats, stamps)
 }
 
+#[cfg(test)]
+#[path = "../../tools/test_support.rs"]
+mod test_support;
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::grids_equal_ignoring_trailing_ws;
+    use std::collections::VecDeque;
+
+    /// The original `VecDeque` + `in_queue` cascade, kept to pin the flat rewrite's results.
+    fn reference_multi_timed_in<C: Cell>(
+        grid: &CellGrid<C>,
+        region: Option<&Region>,
+        mut on_pass: impl FnMut(usize, usize),
+    ) -> (Stats<C>, PassGrid) {
+
+        let mut out = grid.clone();
+        let mut total_removed = 0usize;
+        let mut passes = 0usize;
+        let mut original_rolls = 0usize;
+        let mut removed_per_pass = Vec::new();
+
+        let rows = out.len();
+        let cols = out[0].len();
+
+        // degree[r][c] = how many rolls neighbors cell (r,c) currently has
+        let mut degree =  vec![vec![0u8; cols]; rows];
+
+        // removed_in[r][c] = pass in which cell (r,c) was removed
+        let mut removed_in: PassGrid = vec![vec![None; cols]; rows];
+
+        // 1) compute initial degrees using the shared count_adjacent_rolls
+        //    (only ROLL neighbors count, so pre-removed cells are excluded)
+
+        for r in 0..rows {
+            for c in 0..cols {
+                if out[r][c] == C::ROLL && in_region(region, r, c) {
+                    original_rolls += 1;
+                    degree[r][c] = count_adjacent_rolls(&out, r, c);
+                }
+            }
+        }
+
+        // 2) initial queue: all cells with '@' and degree < 4
+        let mut queue = VecDeque::new();
+        let mut in_queue = vec![vec![false; cols]; rows];
+
+        for r in 0..rows {
+            for c in 0..cols {
+                if out[r][c] == C::ROLL && in_region(region, r, c) && degree[r][c] < 4 {
+                    queue.push_back((r,c));
+                    in_queue[r][c] = true;
+                }
+            }
+        }
+
+        // 3) process in passes
+        while !queue.is_empty() {
+            passes += 1;
+            let mut removed_this_wave = 0usize;
+
+            let layer_size = queue.len();
+            for _ in 0..layer_size {
+                let (r,c) = queue.pop_front().unwrap();
+                in_queue[r][c] = false;
+
+                if out[r][c] != C::ROLL {
+                    continue; // it might have been removed already
+                }
+
+                // remove this roll
+                out[r][c] = C::REMOVED;
+                removed_in[r][c] = Some(passes);
+                total_removed += 1;
+                removed_this_wave += 1;
+
+                // update neighbors' degrees
+                for (dr,dc) in NEIGHBORS {
+                    let nr = r as isize + dr;
+                    let nc = c as isize + dc;
+
+                    if nr < 0 || nr >= rows as isize || nc < 0 || nc >= cols as isize {
+                        continue;
+                    }
+                    let (ur, uc) = (nr as usize, nc as usize);
+
+                    if out[ur][uc] != C::ROLL || !in_region(region, ur, uc) {
+                        continue;
+                    }
+
+                    if degree[ur][uc] > 0 {
+                        degree[ur][uc] -= 1;
+                    }
+
+                    if degree[ur][uc] < 4 && !in_queue[ur][uc] {
+                        queue.push_back((ur, uc));
+                        in_queue[ur][uc] = true;
+                    }
+                }
+            }
+            removed_per_pass.push(removed_this_wave);
+            on_pass(passes, removed_this_wave);
+        }
+
+        let stats = Stats {
+                out,
+                passes,
+                total_removed,
+                original_rolls,
+                removed_per_pass,
+        };
+
+        (stats, removed_in)
+    }
 
     /// Helper to create a grid from a string representation
     fn grid_from_str(s: &str) -> Grid {
@@ -263,6 +857,100 @@ mod tests {
             .count()
     }
 
+    /// Helper to assert a grid matches an expected layout cell-by-cell,
+    /// panicking with the first mismatching (row, col) coordinate
+    fn assert_grid_eq(actual: &Grid, expected: &str) {
+        let expected = grid_from_str(expected);
+        assert_eq!(actual.len(), expected.len(), "row count mismatch");
+        for (r, (got_row, want_row)) in actual.iter().zip(&expected).enumerate() {
+            assert_eq!(got_row.len(), want_row.len(), "column count mismatch in row {r}");
+            for (c, (got, want)) in got_row.iter().zip(want_row).enumerate() {
+                assert_eq!(got, want, "first mismatch at ({r}, {c})");
+            }
+        }
+    }
+
+    #[test]
+    fn test_strict_parse_rejects_unknown_glyph() {
+        let input = "@.x\n.?@\n";
+
+        let lenient = process_input_grid_checked(input, false, 0).unwrap();
+        assert_eq!(lenient, process_input_grid(input));
+        assert_eq!(process_grid_single(&lenient).total_removed, 2);
+
+        assert_eq!(
+            process_input_grid_checked(input, true, 0),
+            Err("Unexpected cell '?' at line 2, column 2".to_string())
+        );
+        assert!(process_input_grid_checked("@.x\n.x@\n", true, 0).is_ok());
+    }
+
+    #[test]
+    fn test_min_len_skips_short_trailing_line() {
+        let input = "@@.\n@.@\n.@@\n@\n";
+
+        assert_eq!(
+            process_input_grid_checked(input, true, 0),
+            Err("Ragged grid: line 4 has 1 cells, expected 3".to_string())
+        );
+
+        let grid = process_input_grid_checked(input, true, 3).unwrap();
+        assert_eq!(grid, process_input_grid("@@.\n@.@\n.@@\n"));
+        assert_eq!(process_grid_single(&grid).total_removed, 6);
+
+        // Strict errors still name the original line
+        assert_eq!(
+            process_input_grid_checked("@\n@.?\n", true, 2),
+            Err("Unexpected cell '?' at line 2, column 3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_empty_grid_removes_nothing() {
+        // `--min-len` can filter every line, and a grid without rolls crops to nothing
+        let filtered = process_input_grid_checked("@@\n@\n", false, 3).unwrap();
+        let (cropped, _) = crop_to_content(&grid_from_str("...\n..."));
+
+        for grid in [filtered, cropped] {
+            assert!(grid.is_empty());
+            assert_eq!(process_grid_single(&grid).total_removed, 0);
+            let stats = process_grid_multi(&grid);
+            assert_eq!((stats.passes, stats.total_removed), (0, 0));
+        }
+    }
+
+    #[test]
+    fn test_write_run_captures_output() {
+        let grid = grid_from_str(".@.\n@@@\n.@.");
+
+        let mut out = Vec::new();
+        write_run(&mut out, &grid, "multi", Rules::default(), PassLog::All, true).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "5\n");
+
+        let mut out = Vec::new();
+        write_run(&mut out, &grid, "multi", Rules::default(), PassLog::All, false).unwrap();
+        let expected = "\
+=== Original grid ===
+.@.
+@@@
+.@.
+
+Running MULTI pass ...
+Pass 1: removed 4 rolls
+Pass 2: removed 1 rolls
+
+ Final Grid with removed accessible rolls
+.x.
+xxx
+.x.
+
+MULTI: passes = 2, total removed = 5 (100.0% of rolls)
+";
+        assert!(grids_equal_ignoring_trailing_ws(&String::from_utf8(out).unwrap(), expected));
+
+        assert!(write_run(&mut Vec::new(), &grid, "triple", Rules::default(), PassLog::All, false).is_err());
+    }
+
     #[test]
     fn test_empty_grid() {
         let grid = grid_from_str("...\n...\n...");
@@ -322,6 +1010,106 @@ mod tests {
         assert_eq!(stats_multi.total_removed, 9);
         assert_eq!(count_rolls(&stats_multi.out), 0);
         assert!(stats_multi.passes > 1); // Should take multiple passes
+        assert_eq!(stats_multi.removed_fraction(), 1.0); // 9 / 9
+        assert_eq!(stats_single.removed_fraction(), 4.0 / 9.0);
+    }
+
+    #[test]
+    fn test_threshold_rules_on_full_three_by_three() {
+        // Corners have 3 neighbors, edges 5, center 8
+        let grid = grid_from_str("@@@\n@@@\n@@@");
+        let with = |threshold| Rules { threshold, ..Rules::default() };
+
+        // Edges sit exactly at 5, so "fewer than 5" still only reaches the corners
+        let single_4 = process_grid_single_with(&grid, with(4));
+        let single_5 = process_grid_single_with(&grid, with(5));
+        assert_grid_eq(&single_4.out, "x@x\n@@@\nx@x");
+        assert_grid_eq(&single_5.out, "x@x\n@@@\nx@x");
+
+        // At 6 the edges go in the same pass; at 3 nothing is accessible
+        assert_grid_eq(&process_grid_single_with(&grid, with(6)).out, "xxx\nx@x\nxxx");
+        assert_eq!(process_grid_single_with(&grid, with(3)).total_removed, 0);
+
+        // Multi at 4: corners, then edges, then the center (down to 4 after the corners)
+        // At 5 that 4 is already accessible, so the center goes with the edges in pass 2
+        assert_eq!(process_grid_multi_with(&grid, with(4)).passes, 3);
+        let multi_5 = process_grid_multi_with(&grid, with(5));
+        assert_eq!((multi_5.passes, multi_5.total_removed), (2, 9));
+        let multi_6 = process_grid_multi_with(&grid, with(6));
+        assert_eq!((multi_6.passes, multi_6.total_removed), (2, 9));
+        assert_eq!(process_grid_multi_with(&grid, with(3)).passes, 0);
+
+        // Defaults are unchanged
+        assert_eq!(process_grid_single(&grid).out, single_4.out);
+        assert_eq!(Rules::default(), with(4));
+        assert_eq!(Rules::default().connectivity, Connectivity::Eight);
+    }
+
+    #[test]
+    fn test_cross_pattern_four_vs_eight_connectivity() {
+        let grid = grid_from_str(".@.\n@@@\n.@.");
+        let four = Rules { connectivity: Connectivity::Four, ..Rules::default() };
+        let eight = Rules::default();
+
+        // Arms touch the other arms diagonally, which only Eight counts; the center sees all 4 either way
+        assert_eq!(count_adjacent_rolls_with(&grid, 0, 1, eight), 3);
+        assert_eq!(count_adjacent_rolls_with(&grid, 0, 1, four), 1);
+        assert_eq!(count_adjacent_rolls_with(&grid, 1, 1, eight), 4);
+        assert_eq!(count_adjacent_rolls_with(&grid, 1, 1, four), 4);
+        assert_eq!(count_adjacent_rolls(&grid, 0, 1), 3);
+
+        // At the default threshold both remove the arms, then the center
+        for rules in [four, eight] {
+            assert_grid_eq(&process_grid_single_with(&grid, rules).out, ".x.\nx@x\n.x.");
+            let multi = process_grid_multi_with(&grid, rules);
+            assert_eq!((multi.passes, multi.total_removed), (2, 5));
+        }
+
+        // At threshold 2 only Four sees the arms as exposed, and the cascade then takes the center
+        let four_2 = Rules { threshold: 2, ..four };
+        let eight_2 = Rules { threshold: 2, ..eight };
+        assert_eq!(process_grid_single_with(&grid, eight_2).total_removed, 0);
+        assert_eq!(process_grid_multi_with(&grid, eight_2).total_removed, 0);
+        assert_eq!(process_grid_single_with(&grid, four_2).total_removed, 4);
+        let multi = process_grid_multi_with(&grid, four_2);
+        assert_eq!((multi.passes, multi.total_removed), (2, 5));
+    }
+
+    #[test]
+    fn test_toroidal_wrap() {
+        let torus = Rules { wrap: true, ..Rules::default() };
+
+        // A lone roll finds nothing, even across the edges
+        let lone = grid_from_str("...\n.@.\n...");
+        assert_eq!(count_adjacent_rolls_with(&lone, 1, 1, torus), 0);
+        let corner = grid_from_str("@..\n...\n...");
+        assert_eq!(count_adjacent_rolls_with(&corner, 0, 0, torus), 0);
+
+        // A full row wraps onto itself: every cell sees both row-mates
+        let row = grid_from_str("...\n@@@\n...");
+        let flat: Vec<u8> = (0..3).map(|c| count_adjacent_rolls(&row, 1, c)).collect();
+        let wrapped: Vec<u8> = (0..3).map(|c| count_adjacent_rolls_with(&row, 1, c, torus)).collect();
+        assert_eq!(flat, vec![1, 2, 1]);
+        assert_eq!(wrapped, vec![2, 2, 2]);
+
+        // Without edges the full 3x3 grid gives every roll 8 neighbors, so nothing goes
+        let full = grid_from_str("@@@\n@@@\n@@@");
+        assert_eq!(process_grid_single_with(&full, torus).total_removed, 0);
+        assert_eq!(process_grid_multi_with(&full, torus).total_removed, 0);
+
+        // A 4x4 ring at threshold 3: flat, its corners (2 neighbors) start a cascade that
+        // takes everything; wrapped, every ring cell also sees the opposite side and stays
+        let ring = grid_from_str("@@@@\n@..@\n@..@\n@@@@");
+        let ring_rules = Rules { threshold: 3, ..torus };
+        assert_eq!(process_grid_multi_with(&ring, Rules { threshold: 3, ..Rules::default() }).total_removed, 12);
+        assert_eq!(process_grid_multi_with(&ring, ring_rules).total_removed, 0);
+    }
+
+    #[test]
+    fn test_removed_fraction_without_rolls() {
+        let stats = process_grid_multi(&grid_from_str("...\n.x."));
+        assert_eq!(stats.original_rolls, 0);
+        assert_eq!(stats.removed_fraction(), 0.0);
     }
 
     #[test]
@@ -341,6 +1129,100 @@ mod tests {
         assert_eq!(stats_multi.total_removed, 5);
         assert_eq!(count_rolls(&stats_multi.out), 0);
         assert_eq!(stats_multi.passes, 2); // Two passes needed
+
+        assert_grid_eq(&stats_single.out, ".x.\nx@x\n.x.");
+        assert_grid_eq(&stats_multi.out, ".x.\nxxx\n.x.");
+    }
+
+    #[test]
+    fn test_removal_passes_cross_pattern() {
+        // Edges go in pass 1, the center only after its neighbors are gone
+        let grid = grid_from_str(".@.\n@@@\n.@.");
+
+        let heatmap = removal_passes(&grid);
+        assert_eq!(
+            heatmap,
+            vec![
+                vec![None, Some(1), None],
+                vec![Some(1), Some(2), Some(1)],
+                vec![None, Some(1), None],
+            ]
+        );
+
+        // Four arms in the first pass, then the center
+        let stats = process_grid_multi(&grid);
+        assert_eq!(stats.removed_per_pass, vec![4, 1]);
+        assert_eq!(stats.removed_per_pass.len(), stats.passes);
+        assert_eq!(stats.removed_per_pass.iter().sum::<usize>(), stats.total_removed);
+        assert!(process_grid_single(&grid).removed_per_pass.is_empty());
+    }
+
+    #[test]
+    fn test_single_multi_diff_cross_pattern() {
+        // The arms go in both runs; only the cascade reaches the center
+        let grid = grid_from_str(".@.\n@@@\n.@.");
+        assert_eq!(single_multi_diff(&grid, 4), vec![(1, 1)]);
+
+        // At threshold 5 the center is already accessible, so nothing differs
+        assert!(single_multi_diff(&grid, 5).is_empty());
+
+        // The stable 5x5 core never cascades past the corners
+        assert!(single_multi_diff(&grid_from_str("@@@@@\n@@@@@\n@@@@@\n@@@@@\n@@@@@"), 4).is_empty());
+    }
+
+    #[test]
+    fn test_resume_from_mid_cascade() {
+        // The cross after pass 1: edges already removed, the center now has no neighbors
+        let grid = grid_from_str(".x.\nx@x\n.x.");
+
+        let stats = process_grid_multi(&grid);
+        assert_grid_eq(&stats.out, ".x.\nxxx\n.x.");
+        assert_eq!(stats.passes, 1);
+        assert_eq!(stats.total_removed, 1);
+        assert_eq!(stats.original_rolls, 1);
+        assert_eq!(removal_passes(&grid)[1][1], Some(1));
+    }
+
+    #[test]
+    fn test_crop_to_content() {
+        let grid = grid_from_str(".....\n.....\n..@@.\n...@.\n.....");
+
+        let (cropped, offset) = crop_to_content(&grid);
+        assert_eq!(cropped, grid_from_str("@@\n.@"));
+        assert_eq!(offset, (2, 2));
+
+        // Same removals, just translated by the offset
+        let full = process_grid_single(&grid);
+        let crop = process_grid_single(&cropped);
+        assert_eq!(full.total_removed, crop.total_removed);
+        assert_eq!(full.out[2 + 1][2 + 1], crop.out[1][1]);
+
+        assert_eq!(crop_to_content(&grid_from_str("...\n...")), (Grid::new(), (0, 0)));
+    }
+
+    #[test]
+    fn test_pass_callback_does_not_change_stats() {
+        let grid = grid_from_str(".@.\n@@@\n.@.");
+
+        let mut seen = Vec::new();
+        let (with_log, _) = process_grid_multi_timed(&grid, |pass, removed| seen.push((pass, removed)));
+        let silent = process_grid_multi(&grid);
+
+        assert_eq!(with_log.out, silent.out);
+        assert_eq!(with_log.passes, silent.passes);
+        assert_eq!(with_log.total_removed, silent.total_removed);
+        assert_eq!(seen, vec![(1, 4), (2, 1)]);
+    }
+
+    #[test]
+    fn test_pass_log_selection() {
+        let shown = |log: PassLog| (1..=7).filter(|&p| log.shows(p, 7)).collect::<Vec<_>>();
+        assert_eq!(shown(PassLog::Silent), Vec::<usize>::new());
+        assert_eq!(shown(PassLog::All), vec![1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(shown(PassLog::FirstLast), vec![1, 7]);
+        assert_eq!(shown(PassLog::EveryNth(3)), vec![3, 6, 7]);
+        assert_eq!(PassLog::parse("3"), Ok(PassLog::EveryNth(3)));
+        assert!(PassLog::parse("0").is_err());
     }
 
     #[test]
@@ -374,6 +1256,13 @@ mod tests {
         assert_eq!(count_rolls(&stats_multi.out), 12); // Same 12 remain
     }
 
+    #[test]
+    #[should_panic(expected = "first mismatch at (1, 1)")]
+    fn test_assert_grid_eq_reports_first_mismatch() {
+        let grid = grid_from_str(".x.\nx@x\n.x.");
+        assert_grid_eq(&grid, ".x.\nxxx\n.x@");
+    }
+
     #[test]
     fn test_count_adjacent_rolls() {
         let grid = grid_from_str("@@@\n@@@\n@@@");
@@ -388,6 +1277,48 @@ mod tests {
         assert_eq!(count_adjacent_rolls(&grid, 0, 1), 5);
     }
 
+    #[test]
+    fn test_adjacency_histogram() {
+        let grid = grid_from_str("@@@\n@@@\n@@@");
+
+        // 4 corners with 3, 4 edges with 5, 1 center with 8
+        assert_eq!(adjacency_histogram(&grid), [0, 0, 0, 4, 0, 4, 0, 0, 1]);
+
+        // Single pass removes exactly the cells counted below the threshold
+        let below: u64 = adjacency_histogram(&grid)[..4].iter().sum();
+        assert_eq!(below as usize, process_grid_single(&grid).total_removed);
+    }
+
+    #[test]
+    fn test_candidate_additions() {
+        // Each roll has 2 neighbors: one more makes it stable at threshold 3
+        let grid = grid_from_str("@@.\n@..\n...");
+        let threshold = |threshold| Rules { threshold, ..Rules::default() };
+        assert_eq!(
+            candidate_additions(&grid, threshold(3)),
+            vec![(0, 2), (1, 1), (1, 2), (2, 0), (2, 1)]
+        );
+        assert!(candidate_additions(&grid, threshold(4)).is_empty());
+
+        // Each arm of the cross touches the other three rolls, so any corner tips two arms to 4
+        assert_eq!(
+            candidate_additions(&grid_from_str(".@.\n@@@\n.@."), Rules::default()),
+            vec![(0, 0), (0, 2), (2, 0), (2, 2)]
+        );
+
+        // Orthogonally, (0, 1) and (1, 0) have one neighbor each; only edge-sharing cells tip them
+        let four = Rules { threshold: 2, connectivity: Connectivity::Four, wrap: false };
+        assert_eq!(candidate_additions(&grid, four), vec![(0, 2), (1, 1), (2, 0)]);
+
+        // On a 3x3 torus every other cell neighbors a lone corner roll
+        let lone = grid_from_str("@..\n...\n...");
+        let wrap = Rules { threshold: 1, connectivity: Connectivity::Eight, wrap: true };
+        assert_eq!(candidate_additions(&lone, Rules { wrap: false, ..wrap }), vec![(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(candidate_additions(&lone, wrap).len(), 8);
+
+        assert!(candidate_additions(&Grid::new(), Rules::default()).is_empty());
+    }
+
     #[test]
     fn test_count_adjacent_with_gaps() {
         let grid = grid_from_str("@.@\n.@.\n@.@");
@@ -463,6 +1394,8 @@ mod tests {
         assert_eq!(stats_multi.total_removed, 4); // Only corners, same as single
         assert_eq!(stats_multi.passes, 1); // Only one pass needed
         assert_eq!(count_rolls(&stats_multi.out), 21); // 25 - 4 = 21 remain
+
+        assert_grid_eq(&stats_multi.out, "x@@@x\n@@@@@\n@@@@@\n@@@@@\nx@@@x");
     }
 
     #[test]
@@ -490,7 +1423,115 @@ mod tests {
         let stats_multi = process_grid_multi(&grid);
         assert_eq!(stats_multi.total_removed, 43);
    
+        // Byte-based processing agrees with the char-based grids cell for cell
+        let bytes = from_bytes(input.as_bytes());
+        let bytes_single = process_grid_single(&bytes);
+        let bytes_multi = process_grid_multi(&bytes);
+        assert_eq!(bytes_single.total_removed, stats_single.total_removed);
+        assert_eq!((bytes_multi.total_removed, bytes_multi.passes), (stats_multi.total_removed, stats_multi.passes));
+        let as_chars = |g: &ByteGrid| -> Grid {
+            g.iter().map(|row| row.iter().map(|&b| b as char).collect()).collect()
+        };
+        assert_eq!(as_chars(&bytes_single.out), stats_single.out);
+        assert_eq!(as_chars(&bytes_multi.out), stats_multi.out);
+        assert_eq!(from_bytes(b"@.\r\n.@\n"), vec![b"@.".to_vec(), b".@".to_vec()]);
     }
-}
 
+    #[test]
+    fn test_region_matches_masked_full_grid() {
+        let grid = grid_from_str("..@@.@@@@.\n\
+                                  @@@.@.@.@@\n\
+                                  @@@@@.@.@@\n\
+                                  @.@@@@..@.\n\
+                                  @@.@@@@.@@\n\
+                                  .@@@@@@@.@\n\
+                                  .@.@.@.@@@\n\
+                                  @.@@@.@@@@\n\
+                                  .@@@@@@@@.\n\
+                                  @.@.@@@.@.");
+        let region = Region { rows: 2..7, cols: 3..8 };
+
+        // Single pass: the full result masked to the region, original rolls elsewhere
+        let full = process_grid_single(&grid);
+        let limited = process_grid_single_in(&grid, Some(&region), Rules::default());
+        for (r, row) in grid.iter().enumerate() {
+            for (c, &cell) in row.iter().enumerate() {
+                let expected = if region.contains(r, c) { full.out[r][c] } else if cell == '@' { '@' } else { '.' };
+                assert_eq!(limited.out[r][c], expected, "mismatch at ({r}, {c})");
+            }
+        }
+        assert_eq!(limited.total_removed, count_removed(&limited.out));
+
+        // Multi pass: outside rolls never go, so removals are a subset of the full run
+        let full = process_grid_multi(&grid);
+        let limited = process_grid_multi_in(&grid, Some(&region));
+        for (r, row) in grid.iter().enumerate() {
+            for (c, &cell) in row.iter().enumerate() {
+                if limited.out[r][c] == 'x' {
+                    assert!(region.contains(r, c) && full.out[r][c] == 'x');
+                } else if !region.contains(r, c) {
+                    assert_eq!(limited.out[r][c], cell);
+                }
+            }
+        }
+        assert!(limited.total_removed > 0 && limited.total_removed < full.total_removed);
+        assert_eq!(process_grid_multi_in(&grid, None).total_removed, full.total_removed);
+    }
 
+    #[test]
+    fn test_flat_cascade_matches_reference() {
+        /// Rendered grid, passes, removed, original rolls, per-cell passes and the pass log.
+        type Outcome = (Vec<u8>, usize, usize, usize, PassGrid, Vec<(usize, usize)>);
+
+        fn run(use_reference: bool, grid: &Grid, region: Option<&Region>) -> Outcome {
+            let mut log = Vec::new();
+            let on_pass = |pass, removed| log.push((pass, removed));
+            let (stats, removed_in) = if use_reference {
+                reference_multi_timed_in(grid, region, on_pass)
+            } else {
+                process_grid_multi_timed_in(grid, region, Rules::default(), on_pass)
+            };
+            let counts: Vec<usize> = log.iter().map(|&(_, removed)| removed).collect();
+            assert_eq!(stats.removed_per_pass, counts);
+            let mut bytes = Vec::new();
+            write_grid(&mut bytes, &stats.out).unwrap();
+            (bytes, stats.passes, stats.total_removed, stats.original_rolls, removed_in, log)
+        }
+        let (flat, reference) = (false, true);
+
+        let aoc = grid_from_str(
+            "..@@.@@@@.\n@@@.@.@.@@\n@@@@@.@.@@\n@.@@@@..@.\n@@.@@@@.@@\n\
+             .@@@@@@@.@\n.@.@.@.@@@\n@.@@@.@@@@\n.@@@@@@@@.\n@.@.@@@.@.",
+        );
+        let region = Region { rows: 2..8, cols: 1..7 };
+        assert_eq!(run(flat, &aoc, None), run(reference, &aoc, None));
+        assert_eq!(run(flat, &aoc, Some(&region)), run(reference, &aoc, Some(&region)));
+
+        // Seeded xorshift so the random grids are the same on every run
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for (rows, cols) in [(1, 1), (1, 17), (9, 4), (23, 31), (40, 40)] {
+            for density in [50, 70, 85] {
+                let grid: Grid = (0..rows)
+                    .map(|_| {
+                        (0..cols)
+                            .map(|_| match next() % 100 {
+                                n if n < 5 => 'x',
+                                n if n < density => '@',
+                                _ => '.',
+                            })
+                            .collect()
+                    })
+                    .collect();
+                assert_eq!(run(flat, &grid, None), run(reference, &grid, None), "{rows}x{cols} at {density}%");
+                let region = Region { rows: rows / 4..rows, cols: 0..cols / 2 + 1 };
+                assert_eq!(run(flat, &grid, Some(&region)), run(reference, &grid, Some(&region)));
+            }
+        }
+    }
+}
@@ -1,6 +1,10 @@
-use std::env;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::io::{self, Write};
 
-const INPUT_URL: &str = "https://adventofcode.com/2025/day/5/input";
+#[path = "../../tools/aoc_io.rs"]
+mod aoc_io;
+use aoc_io::with_input;
 
 
 /// Inclusive interval [start, end]
@@ -10,11 +14,113 @@ struct Interval {
     end: i64
 }
 
+/// Inclusive interval carrying a payload, e.g. the label of a "3-5:A" range.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct LabeledInterval<T> {
+    bounds: Interval,
+    payload: T,
+}
+
+/// Parses a labeled range line "a-b:label" (bounds normalized like `parse_input`).
+#[allow(dead_code)]
+fn parse_labeled_range(line: &str) -> Result<LabeledInterval<String>, String> {
+    let (range, label) = line
+        .trim()
+        .split_once(':')
+        .ok_or_else(|| format!("Bad labeled range '{line}', expected a-b:label"))?;
+    let (a, b) = range
+        .split_once('-')
+        .ok_or_else(|| format!("Bad labeled range '{line}', expected a-b:label"))?;
+
+    let start: i64 = a.trim().parse().map_err(|_| format!("Bad range start in '{line}'"))?;
+    let end: i64 = b.trim().parse().map_err(|_| format!("Bad range end in '{line}'"))?;
+
+    Ok(LabeledInterval {
+        bounds: normalize(Interval { start, end }),
+        payload: label.trim().to_string(),
+    })
+}
+
 /// Parse the input format:
 /// - First section: lines of "a-b" ranges
-/// Then blank line as separator
+/// - Then blank line as separator
 /// - Second section one number per line
+/// 
+/// Ranges are normalized so that start <= end.
 fn parse_input(input: &str) -> (Vec<Interval>, Vec<i64>) {
+    parse_input_as(input, InputFormat::Sections)
+}
+
+/// How the input lays out ranges and queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum InputFormat {
+    /// "a-b" range lines, a blank line, then one query per line
+    #[default]
+    Sections,
+    /// Interleaved "R a-b" / "Q n" lines (see `parse_input_tagged`)
+    Tagged,
+}
+
+/// `parse_input` for either input format.
+fn parse_input_as(input: &str, format: InputFormat) -> (Vec<Interval>, Vec<i64>) {
+    let (raw, numbers) = parse_input_raw_as(input, format);
+    (raw.into_iter().map(normalize).collect(), numbers)
+}
+
+/// `parse_input_raw` for either input format.
+fn parse_input_raw_as(input: &str, format: InputFormat) -> (Vec<Interval>, Vec<i64>) {
+    match format {
+        InputFormat::Sections => parse_input_raw(input),
+        InputFormat::Tagged => parse_input_tagged_raw(input),
+    }
+}
+
+/// Parse the tagged input format, where ranges and queries may interleave:
+/// - "R a-b" is a range
+/// - "Q n" is a query number
+/// - blank lines are ignored
+/// 
+/// Ranges are normalized like `parse_input`, and both lists keep input order.
+fn parse_input_tagged(input: &str) -> (Vec<Interval>, Vec<i64>) {
+    parse_input_as(input, InputFormat::Tagged)
+}
+
+/// Same as `parse_input_tagged`, but keeps ranges exactly as written (start may exceed end).
+fn parse_input_tagged_raw(input: &str) -> (Vec<Interval>, Vec<i64>) {
+    let mut ranges: Vec<Interval> = Vec::new();
+    let mut numbers: Vec<i64> = Vec::new();
+
+    for raw in input.lines() {
+        let line = raw.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let (tag, rest) = line
+            .split_once(char::is_whitespace)
+            .unwrap_or_else(|| panic!("Bad tagged line '{line}', expected 'R a-b' or 'Q n'"));
+
+        match tag {
+            "R" => {
+                let (a, b) = rest
+                    .split_once('-')
+                    .unwrap_or_else(|| panic!("Bad range line '{line}', expected R a-b"));
+
+                let start: i64 = a.trim().parse().expect("Bad range start");
+                let end: i64 = b.trim().parse().expect("Bad range end");
+
+                ranges.push(Interval { start, end });
+            }
+            "Q" => numbers.push(rest.trim().parse().expect("Bad number")),
+            _ => panic!("Bad tag '{tag}' in line '{line}', expected R or Q"),
+        }
+    }
+
+    (ranges, numbers)
+}
+
+/// Same as `parse_input`, but keeps ranges exactly as written (start may exceed end).
+fn parse_input_raw(input: &str) -> (Vec<Interval>, Vec<i64>) {
     let mut ranges: Vec<Interval> = Vec::new();
     let mut numbers: Vec<i64> = Vec::new();
 
@@ -36,13 +142,8 @@ fn parse_input(input: &str) -> (Vec<Interval>, Vec<i64>) {
                 .split_once('-')
                 .unwrap_or_else(|| panic!("Bad range line '{line}', expected a-b"));
 
-            let mut start: i64 = a.trim().parse().expect("Bad range start");
-            let mut end: i64 = b.trim().parse().expect("Bad range end");
-
-            // Normalize in case a > b
-            if start > end {
-                std::mem::swap(&mut start, &mut end);
-            }
+            let start: i64 = a.trim().parse().expect("Bad range start");
+            let end: i64 = b.trim().parse().expect("Bad range end");
 
             ranges.push(Interval { start, end });
         } else {
@@ -54,6 +155,91 @@ fn parse_input(input: &str) -> (Vec<Interval>, Vec<i64>) {
     (ranges, numbers)
 }
 
+/// Normalize an interval in case start > end by swapping the bounds.
+fn normalize(it: Interval) -> Interval {
+    if it.start > it.end {
+        Interval { start: it.end, end: it.start }
+    } else {
+        it
+    }
+}
+
+/// Summary of what normalization and `merge_intervals` did to a raw interval list.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct NormalizationReport {
+    /// Intervals written with start > end that had their bounds swapped
+    swapped: usize,
+    /// Intervals merged into a previous block because they overlapped it
+    overlap_merged: usize,
+    /// Intervals merged into a previous block because they were directly adjacent
+    adjacent_merged: usize,
+}
+
+/// Reports how many raw intervals were swapped, overlap-merged and adjacent-merged.
+/// 
+/// Mirrors `merge_intervals` step by step: after normalizing and sorting, each
+/// interval either starts a new block, overlaps the current block
+/// (it.start <= last.end) or touches it (it.start == last.end + 1).
+fn report_normalization(raw: &[Interval]) -> NormalizationReport {
+    let mut report = NormalizationReport {
+        swapped: raw.iter().filter(|it| it.start > it.end).count(),
+        ..NormalizationReport::default()
+    };
+
+    let mut v: Vec<Interval> = raw.iter().copied().map(normalize).collect();
+    v.sort_by(|a, b| a.start.cmp(&b.start).then_with(|| a.end.cmp(&b.end)));
+
+    let mut last: Option<Interval> = None;
+    for it in v {
+        match last.as_mut() {
+            Some(last) if it.start <= last.end => {
+                report.overlap_merged += 1;
+                last.end = last.end.max(it.end);
+            }
+            Some(last) if it.start == last.end.saturating_add(1) => {
+                report.adjacent_merged += 1;
+                last.end = it.end;
+            }
+            _ => last = Some(it),
+        }
+    }
+
+    report
+}
+
+/// Lists every pair of raw intervals that share at least one integer, by index.
+/// 
+/// Sweeps the (normalized) intervals in order of start, keeping the ones still
+/// open; each new interval overlaps exactly the open ones whose end reaches its
+/// start. Touching intervals ([1,2] and [3,4]) do not overlap here even though
+/// `merge_intervals` joins them. Pairs are `(i, j)` with `i < j`, sorted.
+/// 
+/// Example:
+/// [3,5] + [4,6] + [10,14] -> [(0, 1)]
+/// 
+/// Time: O(n log n + n * open + pairs)
+fn overlapping_pairs(raw: &[Interval]) -> Vec<(usize, usize)> {
+    let v: Vec<Interval> = raw.iter().copied().map(normalize).collect();
+    let mut order: Vec<usize> = (0..v.len()).collect();
+    order.sort_by(|&a, &b| {
+        v[a].start.cmp(&v[b].start).then_with(|| v[a].end.cmp(&v[b].end))
+    });
+
+    let mut open: Vec<usize> = Vec::new();
+    let mut pairs: Vec<(usize, usize)> = Vec::new();
+
+    for idx in order {
+        let it = v[idx];
+        // Intervals that ended before this start can't overlap anything later either
+        open.retain(|&o| v[o].end >= it.start);
+        pairs.extend(open.iter().map(|&o| (o.min(idx), o.max(idx))));
+        open.push(idx);
+    }
+
+    pairs.sort_unstable();
+    pairs
+}
+
 /// Merge ranges so that the result is:
 /// - sorted by start
 /// - non-overlapping
@@ -91,6 +277,76 @@ fn merge_intervals(mut v: Vec<Interval>) -> Vec<Interval> {
     merged
 }
 
+/// Same merge as `merge_intervals`, but also reports provenance: for every merged
+/// interval, the indices (into `v`, ascending) of the original intervals that formed it.
+/// 
+/// Example:
+/// [3,5] + [10,14] + [12,18]
+/// merges to [3,5] from [0] and [10,18] from [1, 2]
+#[allow(dead_code)]
+fn merge_intervals_with_sources(v: &[Interval]) -> Vec<(Interval, Vec<usize>)> {
+    // Sort indices instead of the intervals so each one keeps its origin
+    let mut order: Vec<usize> = (0..v.len()).collect();
+    order.sort_by(|&a, &b| {
+        v[a].start.cmp(&v[b].start).then_with(|| v[a].end.cmp(&v[b].end))
+    });
+
+    let mut merged: Vec<(Interval, Vec<usize>)> = Vec::with_capacity(v.len());
+
+    for idx in order {
+        let it = v[idx];
+        if let Some((last, sources)) = merged.last_mut() {
+            // Same inclusive overlap/adjacency rule as `merge_intervals`
+            if it.start <= last.end.saturating_add(1) {
+                if it.end > last.end {
+                    last.end = it.end;
+                }
+                sources.push(idx);
+                continue;
+            }
+        }
+        merged.push((it, vec![idx]));
+    }
+
+    for (_, sources) in &mut merged {
+        sources.sort_unstable();
+    }
+    merged
+}
+
+/// Same merge as `merge_intervals` for labeled intervals: every merged
+/// interval collects the payloads of the intervals that formed it into a set.
+/// 
+/// The bounds of the result are exactly `merge_intervals` of the bounds, so
+/// the counting and lookup functions apply to them unchanged.
+/// 
+/// Example:
+/// [3,5]:A + [4,6]:B + [10,14]:A
+/// merges to [3,6] with {A, B} and [10,14] with {A}
+#[allow(dead_code)]
+fn merge_labeled_intervals<T: Ord>(mut v: Vec<LabeledInterval<T>>) -> Vec<LabeledInterval<BTreeSet<T>>> {
+    v.sort_by(|a, b| {
+        a.bounds.start.cmp(&b.bounds.start).then_with(|| a.bounds.end.cmp(&b.bounds.end))
+    });
+
+    let mut merged: Vec<LabeledInterval<BTreeSet<T>>> = Vec::with_capacity(v.len());
+
+    for LabeledInterval { bounds: it, payload } in v {
+        if let Some(last) = merged.last_mut() {
+            // Same inclusive overlap/adjacency rule as `merge_intervals`
+            if it.start <= last.bounds.end.saturating_add(1) {
+                if it.end > last.bounds.end {
+                    last.bounds.end = it.end;
+                }
+                last.payload.insert(payload);
+                continue;
+            }
+        }
+        merged.push(LabeledInterval { bounds: it, payload: BTreeSet::from([payload]) });
+    }
+    merged
+}
+
 /// Check if x belongs to any merged interval.
 /// Merged intervals are sorted by start and disjoint.
 /// 
@@ -98,6 +354,11 @@ fn merge_intervals(mut v: Vec<Interval>) -> Vec<Interval> {
 /// Then the candidate is the interval just before that (idx-1)
 /// because it has the largest start <= x.
 fn contains(merged: &[Interval], x: i64) -> bool {
+    locate(merged, x).is_some()
+}
+
+/// Index of the merged interval containing x, if any (same search as `contains`).
+fn locate(merged: &[Interval], x: i64) -> Option<usize> {
     // partition_point returns the first index where predicate is false.
     // Here predicate is: interval.start <= x
     // So idx = number of intervals with start <= x.
@@ -105,12 +366,26 @@ fn contains(merged: &[Interval], x: i64) -> bool {
 
     if idx == 0 {
         // All intervals.start > x, so x can't be inside any interval.
-        return false;
+        return None;
     }
 
     // Candidate interval: last one with start <= x
     let it = merged[idx - 1];
-    x <= it.end
+    (x <= it.end).then_some(idx - 1)
+}
+
+/// Counts how many query numbers fall in each merged interval.
+/// 
+/// Returns one count per merged interval (in order), followed by a final
+/// bucket for queries no interval covers, so the counts sum to `numbers.len()`.
+#[allow(dead_code)]
+fn query_bucketing(merged: &[Interval], numbers: &[i64]) -> Vec<u64> {
+    let mut buckets = vec![0u64; merged.len() + 1];
+    for &x in numbers {
+        let bucket = locate(merged, x).unwrap_or(merged.len());
+        buckets[bucket] += 1;
+    }
+    buckets
 }
 
 
@@ -145,6 +420,78 @@ fn count_interval_members(merged: &[Interval]) -> u64 {
     count
 }
 
+/// Counts the integers contained in merged intervals that also lie within `[lo, hi]`.
+/// 
+/// Each interval is clamped to the bound before applying the inclusive
+/// (end - start) + 1 count; intervals entirely outside the bound are skipped.
+/// 
+/// # Example
+/// Intervals [3,5] and [10,20] bounded to [4,12]:
+/// - [3,5] clamps to [4,5]: 2 integers
+/// - [10,20] clamps to [10,12]: 3 integers
+/// - Total: 5 integers
+#[allow(dead_code)]
+fn count_interval_members_bounded(merged: &[Interval], lo: i64, hi: i64) -> u64 {
+    let mut count = 0;
+
+    for it in merged {
+        let start = it.start.max(lo);
+        let end = it.end.min(hi);
+        if start > end {
+            continue;
+        }
+        count += (end - start) as u64;
+        count += 1;
+    }
+
+    count
+}
+
+/// Lists every integer covered by the merged intervals, in order.
+/// 
+/// A debugging aid for small inputs only: coverage can easily run into the
+/// billions, so if the total count exceeds `limit` an error is returned before
+/// anything is allocated.
+#[allow(dead_code)]
+fn enumerate_covered(merged: &[Interval], limit: usize) -> Result<Vec<i64>, String> {
+    let total = count_interval_members(merged);
+    if total > limit as u64 {
+        return Err(format!("{total} covered integers exceed the limit of {limit}"));
+    }
+
+    Ok(merged.iter().flat_map(|it| it.start..=it.end).collect())
+}
+
+/// Counts the integers covered by both merged interval sets.
+/// 
+/// Two-pointer sweep over the sorted sets: each overlapping pair contributes
+/// its clamped intersection to the count, then whichever interval ends first
+/// is advanced. The intersection itself is never built.
+/// 
+/// # Example
+/// [0,10] and [5,15] overlap in [5,10]: 6 integers
+#[allow(dead_code)]
+fn overlap_size(a: &[Interval], b: &[Interval]) -> u64 {
+    let (mut i, mut j) = (0, 0);
+    let mut count = 0;
+
+    while i < a.len() && j < b.len() {
+        let start = a[i].start.max(b[j].start);
+        let end = a[i].end.min(b[j].end);
+        if start <= end {
+            count += count_interval_members(&[Interval { start, end }]);
+        }
+
+        if a[i].end < b[j].end {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    count
+}
+
 /// Process input to find the amount of numbers belonging to a range
 /// return the value.
 fn process_input_part1(input: &str) -> u64 {
@@ -164,31 +511,129 @@ fn process_input_part2(input: &str) -> u64 {
     let (ranges, _numbers) = parse_input(input);
     let merged = merge_intervals(ranges);
 
-    let count = count_interval_members(&merged);
+    count_interval_members(&merged)
+}
 
-    count
+/// Merged intervals plus both answers, serializable for feeding into other tools
+#[derive(Debug, Serialize)]
+struct Summary {
+    merged: Vec<(i64, i64)>,
+    part1_matches: u64,
+    part2_total: u64,
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    
-    let session = env::var("AOC_SESSION")
-        .map_err(|_| "AOC_SESSION environment variable is not set")?;
+/// Merge once, then compute both answers from the same merged set.
+fn summarize_parsed(ranges: Vec<Interval>, numbers: Vec<i64>) -> Summary {
+    let merged = merge_intervals(ranges);
 
-    let body = ureq::get(INPUT_URL)
-        .header("Cookie", &format!("session={session}"))
-        .call()?
-        .into_body()
-        .read_to_string()?;
+    let part1_matches = numbers.iter().filter(|&&x| contains(&merged, x)).count() as u64;
+    let part2_total = count_interval_members(&merged);
 
+    Summary {
+        merged: merged.iter().map(|it| (it.start, it.end)).collect(),
+        part1_matches,
+        part2_total,
+    }
+}
 
-    let count = process_input_part1(&body);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Answers are already printed bare; `--raw` is accepted for consistency with other days
+    // `json` prints the merged intervals and both answers as a single JSON object
+    let report_mode = std::env::args().skip(1).any(|arg| arg == "report");
+    // `overlaps` lists which input ranges overlap each other (by line index) on stderr
+    let overlaps_mode = std::env::args().skip(1).any(|arg| arg == "overlaps");
+    let json_mode = std::env::args().skip(1).any(|arg| arg == "json");
+    // `tagged` reads interleaved "R a-b" / "Q n" lines instead of two blank-separated
+    // sections; it combines with any of the other modes
+    let format = if std::env::args().skip(1).any(|arg| arg == "tagged") {
+        InputFormat::Tagged
+    } else {
+        InputFormat::Sections
+    };
+    // `buckets` prints how many queries land in each merged interval, then the uncovered ones
+    let buckets_mode = std::env::args().skip(1).any(|arg| arg == "buckets");
+    let output = if buckets_mode {
+        OutputMode::Buckets
+    } else if json_mode {
+        OutputMode::Json
+    } else {
+        OutputMode::Answers
+    };
+
+    with_input(5, |body| {
+        if report_mode {
+            let (raw, _numbers) = parse_input_raw_as(body, format);
+            let report = report_normalization(&raw);
+            eprintln!(
+                "Normalization: {} swapped, {} overlap-merged, {} adjacent-merged",
+                report.swapped, report.overlap_merged, report.adjacent_merged
+            );
+        }
+        if overlaps_mode {
+            let (raw, _numbers) = parse_input_raw_as(body, format);
+            let pairs = overlapping_pairs(&raw);
+            eprintln!("Overlapping ranges: {}", pairs.len());
+            for (i, j) in pairs {
+                eprintln!(
+                    "  #{i} {}-{} overlaps #{j} {}-{}",
+                    raw[i].start, raw[i].end, raw[j].start, raw[j].end
+                );
+            }
+        }
+
+        write_output(&mut io::stdout().lock(), body, format, output)
+    })
+}
+
+/// What `main` writes to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputMode {
+    /// Part1 then part2, one bare number per line
+    Answers,
+    /// Queries per merged interval, then the uncovered count
+    Buckets,
+    /// `Summary` as a single JSON object
+    Json,
+}
 
-    println!("{}", count);
+/// Parses `body` as `format`, solves it and writes the selected output to `out`.
+fn write_output(
+    out: &mut impl Write,
+    body: &str,
+    format: InputFormat,
+    mode: OutputMode,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match (mode, format) {
+        (OutputMode::Answers, InputFormat::Sections) => {
+            let count = process_input_part1(body);
 
-    let total = process_input_part2(&body);
+            writeln!(out, "{}", count)?;
 
-    println!("{}", total);
+            let total = process_input_part2(body);
 
+            writeln!(out, "{}", total)?;
+        }
+        (OutputMode::Answers, InputFormat::Tagged) => {
+            let (ranges, numbers) = parse_input_tagged(body);
+            let summary = summarize_parsed(ranges, numbers);
+            writeln!(out, "{}", summary.part1_matches)?;
+            writeln!(out, "{}", summary.part2_total)?;
+        }
+        (OutputMode::Buckets, _) => {
+            let (ranges, numbers) = parse_input_as(body, format);
+            let merged = merge_intervals(ranges);
+            let buckets = query_bucketing(&merged, &numbers);
+            for (it, count) in merged.iter().zip(&buckets) {
+                writeln!(out, "{}-{}: {count}", it.start, it.end)?;
+            }
+            writeln!(out, "uncovered: {}", buckets[merged.len()])?;
+        }
+        (OutputMode::Json, _) => {
+            let (ranges, numbers) = parse_input_as(body, format);
+            serde_json::to_writer(&mut *out, &summarize_parsed(ranges, numbers))?;
+            writeln!(out)?;
+        }
+    }
     Ok(())
 }
 
@@ -231,4 +676,201 @@ mod tests {
 
  
     }
-}    
+
+    #[test]
+    fn summary_serializes_to_json() {
+        let input = "3-5\n10-14\n16-20\n12-18\n\n1\n5\n8\n11\n17\n32\n";
+
+        let (ranges, numbers) = parse_input(input);
+        let json = serde_json::to_string(&summarize_parsed(ranges, numbers)).unwrap();
+        assert_eq!(
+            json,
+            r#"{"merged":[[3,5],[10,20]],"part1_matches":3,"part2_total":14}"#
+        );
+    }
+
+    #[test]
+    fn output_modes_write_expected_bytes() {
+        let input = "3-5\n10-14\n16-20\n12-18\n\n1\n5\n8\n11\n17\n32\n";
+        let written = |mode| {
+            let mut out = Vec::new();
+            write_output(&mut out, input, InputFormat::Sections, mode).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        assert_eq!(written(OutputMode::Answers), "3\n14\n");
+        assert_eq!(written(OutputMode::Buckets), "3-5: 1\n10-20: 2\nuncovered: 3\n");
+        assert_eq!(
+            written(OutputMode::Json),
+            "{\"merged\":[[3,5],[10,20]],\"part1_matches\":3,\"part2_total\":14}\n"
+        );
+    }
+
+    #[test]
+    fn tagged_input_combines_with_every_output_mode() {
+        let sections = "3-5\n10-14\n16-20\n12-18\n\n1\n5\n8\n11\n17\n32\n";
+        let tagged = "R 3-5\nQ 1\nR 10-14\nQ 5\nQ 8\n\nR 16-20\nQ 11\nR 12-18\nQ 17\nQ 32\n";
+        let written = |body, format, mode| {
+            let mut out = Vec::new();
+            write_output(&mut out, body, format, mode).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        for mode in [OutputMode::Answers, OutputMode::Buckets, OutputMode::Json] {
+            assert_eq!(
+                written(tagged, InputFormat::Tagged, mode),
+                written(sections, InputFormat::Sections, mode)
+            );
+        }
+
+        // Report/overlaps read the raw ranges, so swapped bounds survive tagged parsing too
+        let (raw, numbers) = parse_input_raw_as("R 9-4\nQ 5\n", InputFormat::Tagged);
+        assert_eq!(raw, vec![Interval { start: 9, end: 4 }]);
+        assert_eq!(numbers, vec![5]);
+    }
+
+    #[test]
+    fn merge_with_sources_tracks_provenance() {
+        let raw = [
+            Interval { start: 3, end: 5 },
+            Interval { start: 10, end: 14 },
+            Interval { start: 12, end: 18 },
+        ];
+        let merged = merge_intervals_with_sources(&raw);
+        assert_eq!(
+            merged,
+            vec![
+                (Interval { start: 3, end: 5 }, vec![0]),
+                (Interval { start: 10, end: 18 }, vec![1, 2]),
+            ]
+        );
+
+        let bounds: Vec<Interval> = merged.iter().map(|(it, _)| *it).collect();
+        assert_eq!(bounds, merge_intervals(raw.to_vec()));
+    }
+
+    #[test]
+    fn query_bucketing_counts_per_interval() {
+        let input = "3-5\n10-14\n16-20\n12-18\n\n1\n5\n8\n11\n17\n32\n";
+        let (ranges, numbers) = parse_input(input);
+        let merged = merge_intervals(ranges);
+
+        // [3,5] holds 5; [10,20] holds 11 and 17; 1, 8 and 32 are uncovered
+        assert_eq!(query_bucketing(&merged, &numbers), vec![1, 2, 3]);
+        assert_eq!(locate(&merged, 17), Some(1));
+        assert_eq!(locate(&merged, 8), None);
+    }
+
+    #[test]
+    fn tagged_input_matches_blank_line_sections() {
+        let sections = "3-5\n10-14\n16-20\n12-18\n\n1\n5\n8\n11\n17\n32\n";
+        let tagged = "R 3-5\nQ 1\nR 10-14\nQ 5\nQ 8\n\nR 16-20\nQ 11\nR 12-18\nQ 17\nQ 32\n";
+
+        let (ranges, numbers) = parse_input_tagged(tagged);
+        assert_eq!((ranges.clone(), numbers.clone()), parse_input(sections));
+
+        let summary = summarize_parsed(ranges, numbers);
+        assert_eq!((summary.part1_matches, summary.part2_total), (3, 14));
+    }
+
+    #[test]
+    fn enumerate_covered_lists_small_sets() {
+        let merged = [Interval { start: 3, end: 5 }, Interval { start: 10, end: 12 }];
+        assert_eq!(enumerate_covered(&merged, 6), Ok(vec![3, 4, 5, 10, 11, 12]));
+        assert_eq!(
+            enumerate_covered(&merged, 5),
+            Err("6 covered integers exceed the limit of 5".to_string())
+        );
+    }
+
+    #[test]
+    fn overlap_size_counts_shared_integers() {
+        let a = [Interval { start: 0, end: 10 }];
+        let b = [Interval { start: 5, end: 15 }];
+        assert_eq!(overlap_size(&a, &b), 6);
+        assert_eq!(overlap_size(&b, &a), 6);
+
+        let a = [Interval { start: 3, end: 5 }, Interval { start: 10, end: 20 }];
+        let b = [Interval { start: 0, end: 3 }, Interval { start: 12, end: 14 }, Interval { start: 19, end: 30 }];
+        assert_eq!(overlap_size(&a, &b), 1 + 3 + 2);
+        assert_eq!(overlap_size(&a, &[]), 0);
+    }
+
+    #[test]
+    fn bounded_count_clamps_intervals() {
+        let merged = [Interval { start: 3, end: 5 }, Interval { start: 10, end: 20 }];
+        assert_eq!(count_interval_members_bounded(&merged, 4, 12), 5);
+        assert_eq!(count_interval_members_bounded(&merged, 6, 9), 0);
+        assert_eq!(count_interval_members_bounded(&merged, i64::MIN, i64::MAX), 14);
+    }
+
+    #[test]
+    fn normalization_report_counts_swaps_and_merges() {
+        let (raw, _) = parse_input_raw("5-3\n10-14\n12-18\n");
+        assert_eq!(
+            report_normalization(&raw),
+            NormalizationReport { swapped: 1, overlap_merged: 1, adjacent_merged: 0 }
+        );
+
+        let touching = [Interval { start: 1, end: 2 }, Interval { start: 3, end: 4 }];
+        assert_eq!(report_normalization(&touching).adjacent_merged, 1);
+    }
+
+    #[test]
+    fn overlapping_pairs_by_index() {
+        let raw = [
+            Interval { start: 3, end: 5 },
+            Interval { start: 4, end: 6 },
+            Interval { start: 10, end: 14 },
+        ];
+        assert_eq!(overlapping_pairs(&raw), vec![(0, 1)]);
+
+        // Disjoint and merely touching ranges report nothing
+        let (disjoint, _) = parse_input_raw("1-2
+3-4
+10-14
+");
+        assert_eq!(overlapping_pairs(&disjoint), vec![]);
+        assert_eq!(overlapping_pairs(&[]), vec![]);
+
+        // Indices stay those of the input order, and swapped bounds still count
+        let (raw, _) = parse_input_raw("12-18
+3-5
+20-10
+17-17
+");
+        assert_eq!(overlapping_pairs(&raw), vec![(0, 2), (0, 3), (2, 3)]);
+    }
+
+    #[test]
+    fn labeled_merge_collects_payloads() {
+        let raw: Vec<LabeledInterval<String>> = ["3-5:A", "4-6:B"]
+            .into_iter()
+            .map(|line| parse_labeled_range(line).unwrap())
+            .collect();
+        let merged = merge_labeled_intervals(raw);
+        assert_eq!(
+            merged,
+            vec![LabeledInterval {
+                bounds: Interval { start: 3, end: 6 },
+                payload: BTreeSet::from(["A".to_string(), "B".to_string()]),
+            }]
+        );
+
+        // Bounds match the plain merge, so counting works on them as before
+        let raw = vec![
+            LabeledInterval { bounds: Interval { start: 10, end: 14 }, payload: 'A' },
+            LabeledInterval { bounds: Interval { start: 3, end: 5 }, payload: 'A' },
+            LabeledInterval { bounds: Interval { start: 12, end: 18 }, payload: 'C' },
+        ];
+        let bounds: Vec<Interval> = raw.iter().map(|it| it.bounds).collect();
+        let merged = merge_labeled_intervals(raw);
+        let merged_bounds: Vec<Interval> = merged.iter().map(|it| it.bounds).collect();
+        assert_eq!(merged_bounds, merge_intervals(bounds));
+        assert_eq!(merged[1].payload, BTreeSet::from(['A', 'C']));
+        assert_eq!(count_interval_members(&merged_bounds), 3 + 9);
+
+        assert_eq!(parse_labeled_range("5-3:x").unwrap().bounds, Interval { start: 3, end: 5 });
+        assert!(parse_labeled_range("3-5").is_err());
+    }
+}
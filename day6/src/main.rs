@@ -1,21 +1,96 @@
-use std::env;
+use num_bigint::BigUint;
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
 
-const INPUT_URL: &str = "https://adventofcode.com/2025/day/6/input";
+#[path = "../../tools/aoc_io.rs"]
+mod aoc_io;
+use aoc_io::{with_input, write_answer};
 
 /// Returns non-empty lines (trimming only for emptiness; keeps original spacing).
 #[inline]
-fn non_empty_lines<'a>(input: &'a str) -> Vec<&'a str> {
+fn non_empty_lines(input: &str) -> Vec<&str> {
     input.lines().filter(|l| !l.trim().is_empty()).collect()
 }
 
-/// Parse operator tokens from a whitespace-separated line (`+` or `*`)
+/// Column separator used by the part1 tokenizers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Separator {
+    /// Any run of ASCII whitespace separates columns (AoC default)
+    Whitespace,
+    /// A single delimiter byte separates columns (e.g. `,` for CSV-style rows);
+    /// whitespace around each token is ignored
+    Byte(u8),
+}
+
+/// Parse operator tokens from a separated line (`+` or `*`)
+#[inline]
+fn parse_ops_tokens(line: &str, sep: Separator) -> Vec<u8> {
+    let op_of = |t: &str| {
+        let b = t.as_bytes()[0];
+        debug_assert!(b == b'+' || b == b'*');
+        b
+    };
+    match sep {
+        Separator::Whitespace => line.split_whitespace().map(op_of).collect(),
+        Separator::Byte(d) => line
+            .split(d as char)
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(op_of)
+            .collect(),
+    }
+}
+
+/// Integer scanner over a byte slice, collecting all unsigned ints split by `sep`.
 #[inline]
-fn parse_ops_tokens(line: &str) -> Vec<u8> {
-    line.split_whitespace()
-        .map(|t| {
-            let b = t.as_bytes()[0];
-            debug_assert!(b == b'+' || b == b'*');
-            b
+fn parse_u128_sep(bytes: &[u8], sep: Separator, out: &mut Vec<u128>) {
+    match sep {
+        Separator::Whitespace => parse_u128_ws(bytes, out),
+        Separator::Byte(d) => {
+            out.clear();
+            for token in bytes.split(|&b| b == d) {
+                let token = token.trim_ascii();
+                if token.is_empty() {
+                    continue;
+                }
+                let mut v: u128 = 0;
+                for &c in token {
+                    debug_assert!(c.is_ascii_digit());
+                    v = v * 10 + (c - b'0') as u128;
+                }
+                out.push(v);
+            }
+        }
+    }
+}
+
+/// A token `parse_ints` could not convert, with its 0-based position in the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ParseIntsError {
+    index: usize,
+    token: String,
+}
+
+impl fmt::Display for ParseIntsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid integer '{}' at token {}", self.token, self.index)
+    }
+}
+
+impl std::error::Error for ParseIntsError {}
+
+/// Parses every whitespace-separated token of `s` as `T`.
+/// 
+/// Unlike the fast scanners above this validates input: the first token that
+/// fails to parse is reported with its position instead of being skipped.
+fn parse_ints<T: FromStr>(s: &str) -> Result<Vec<T>, ParseIntsError> {
+    s.split_whitespace()
+        .enumerate()
+        .map(|(index, token)| {
+            token.parse().map_err(|_| ParseIntsError { index, token: token.to_string() })
         })
         .collect()
 }
@@ -49,6 +124,40 @@ fn parse_u128_ws(bytes: &[u8], out: &mut Vec<u128>) {
     }
 }
 
+/// Layout options for the fixed-width part2 grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct GridOptions {
+    /// Tab stops used when expanding '\t' to spaces before measuring columns
+    tab_width: usize,
+    /// Longest accepted line (after tab expansion); `None` disables the check
+    max_width: Option<usize>,
+}
+
+impl Default for GridOptions {
+    fn default() -> Self {
+        GridOptions { tab_width: 8, max_width: None }
+    }
+}
+
+/// Expands tabs to spaces, advancing to the next multiple of `tab_width`.
+/// Lines without tabs are returned borrowed.
+fn expand_tabs(line: &str, tab_width: usize) -> Cow<'_, str> {
+    if !line.contains('\t') {
+        return Cow::Borrowed(line);
+    }
+    let tab_width = tab_width.max(1);
+    let mut out = String::with_capacity(line.len() + tab_width);
+    for ch in line.chars() {
+        if ch == '\t' {
+            let pad = tab_width - out.len() % tab_width;
+            out.extend(std::iter::repeat_n(' ', pad));
+        } else {
+            out.push(ch);
+        }
+    }
+    Cow::Owned(out)
+}
+
 /// Generic block splitterL returns contiguous [start, end) ranges of non-separator columns.
 #[inline]
 fn split_blocks<F>(width: usize, mut is_sep: F) -> Vec<(usize, usize)>
@@ -82,29 +191,30 @@ where
 /// Each column is one "problem": combine all numbers in that column using the operator
 /// Then sum all column results
 /// 
-fn process_input_part1(input: &str) -> u128 {
+/// `sep` selects how columns are split (whitespace by default, or a delimiter byte).
+/// 
+fn process_input_part1(input: &str, sep: Separator) -> u128 {
     // Keep non-empty lines (trailing newline is common).
     let mut lines: Vec<&str> = non_empty_lines(input);
     assert!(!lines.is_empty(), "empty input");
 
     // Last line = operators
     let op_line = lines.pop().unwrap();
-    let ops: Vec<u8> = parse_ops_tokens(op_line);
+    let ops: Vec<u8> = parse_ops_tokens(op_line, sep);
     let cols = ops.len();
     assert!(cols > 0, "no operators found");
 
     // Column accumulators; initialized based on op
-    let mut acc: Vec<u128> = Vec::with_capacity(cols);
-    acc.resize(cols, 0);
-    for (i, &op) in ops.iter().enumerate() {
-        acc[i] = if op == b'+' { 0 } else { 1 };
-    }
+    let mut acc: Vec<u128> = ops
+        .iter()
+        .map(|&op| if op == b'+' { 0 } else { 1 })
+        .collect();
 
     let mut tmp_nums: Vec<u128> = Vec::new();
     
     // Previous lines = operand rows
     for (r, line) in lines.iter().enumerate() {
-       parse_u128_ws(line.as_bytes(), &mut tmp_nums);
+       parse_u128_sep(line.as_bytes(), sep, &mut tmp_nums);
        if tmp_nums.len() != cols {
             panic!(
                 "row {} has {} numbers but operator row has {}",
@@ -127,6 +237,59 @@ fn process_input_part1(input: &str) -> u128 {
 
 }
 
+/// Validating part1 for whitespace-separated input.
+/// 
+/// Operand rows go through `parse_ints` and operators must be `+` or `*`, so a
+/// malformed token or a short row is reported with its 1-based input line
+/// instead of being skipped or panicking.
+fn process_input_part1_strict(input: &str) -> Result<u128, String> {
+    let mut lines: Vec<(usize, &str)> = input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .collect();
+    let (op_index, op_line) = lines.pop().ok_or("empty input")?;
+
+    let ops: Vec<u8> = op_line
+        .split_whitespace()
+        .enumerate()
+        .map(|(index, token)| match token {
+            "+" => Ok(b'+'),
+            "*" => Ok(b'*'),
+            _ => Err(format!("line {}: invalid operator '{token}' at token {index}", op_index + 1)),
+        })
+        .collect::<Result<_, _>>()?;
+    if ops.is_empty() {
+        return Err("no operators found".to_string());
+    }
+
+    let mut acc: Vec<u128> = ops
+        .iter()
+        .map(|&op| if op == b'+' { 0 } else { 1 })
+        .collect();
+
+    for (i, line) in lines {
+        let nums = parse_ints::<u128>(line).map_err(|e| format!("line {}: {e}", i + 1))?;
+        if nums.len() != ops.len() {
+            return Err(format!(
+                "line {} has {} numbers but operator row has {}",
+                i + 1,
+                nums.len(),
+                ops.len()
+            ));
+        }
+        for ((a, &op), v) in acc.iter_mut().zip(&ops).zip(nums) {
+            if op == b'+' {
+                *a += v;
+            } else {
+                *a *= v;
+            }
+        }
+    }
+
+    Ok(acc.into_iter().sum())
+}
+
 /// Process input for AoC challenge day 6 part 2
 /// 
 /// Input format:
@@ -138,8 +301,141 @@ fn process_input_part1(input: &str) -> u128 {
 /// - for each block, each character-column with digits is one operant (top -> bottom)
 /// - operator is in the bottom row somewhere within the block
 /// 
-fn process_input_part2(input: &str) -> u128 {
-    let mut lines = non_empty_lines(input);
+/// Tabs are expanded to spaces (per `opts.tab_width`) before measuring widths,
+/// so tab-indented aligned inputs split into the same blocks as space-aligned ones.
+fn process_input_part2(input: &str, opts: &GridOptions) -> u128 {
+    evaluate_part2_blocks(input, opts)
+        .into_iter()
+        .map(|(_, value)| value)
+        .sum()
+}
+
+/// Like `process_input_part2`, but first rejects any line wider than
+/// `opts.max_width` so malformed giant inputs fail before rows are padded.
+fn process_input_part2_checked(input: &str, opts: &GridOptions) -> Result<u128, String> {
+    check_max_width(input, opts)?;
+    Ok(process_input_part2(input, opts))
+}
+
+/// Rejects the first line wider than `opts.max_width` (after tab expansion), if set.
+/// 
+/// Every input line is checked, blank ones included, and errors report the
+/// 1-based line number in `input`.
+fn check_max_width(input: &str, opts: &GridOptions) -> Result<(), String> {
+    if let Some(max_width) = opts.max_width {
+        for (i, line) in input.lines().enumerate() {
+            let width = expand_tabs(line, opts.tab_width).len();
+            if width > max_width {
+                return Err(format!(
+                    "line {} is {width} columns wide, exceeding max width {max_width}",
+                    i + 1
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Finds the part2 block with the largest result.
+///
+/// Returns `(block index, operator, value)`, or `None` if the grid has no
+/// blocks; on ties the leftmost block wins.
+fn dominant_block(input: &str, opts: &GridOptions) -> Option<(usize, u8, u128)> {
+    evaluate_part2_blocks(input, opts)
+        .into_iter()
+        .enumerate()
+        .max_by_key(|&(i, (_, value))| (value, Reverse(i)))
+        .map(|(i, (op, value))| (i, op, value))
+}
+
+/// Exact part2 total that never overflows.
+/// 
+/// Each block is folded on the fast `u128` path with checked arithmetic; only
+/// a block whose fold overflows is recomputed with `BigUint`. Block results
+/// are summed as `BigUint`, so the total itself may also exceed `u128`.
+fn process_input_part2_exact(input: &str, opts: &GridOptions) -> BigUint {
+    let (rows, blocks) = part2_layout(input, opts);
+    blocks
+        .into_iter()
+        .map(|(op, start, end)| {
+            fold_block_checked(&rows, start, end, op)
+                .map(BigUint::from)
+                .unwrap_or_else(|| fold_block_big(&rows, start, end, op))
+        })
+        .sum()
+}
+
+/// Folds the operands of block `[start, end)` with `op` using checked `u128`
+/// arithmetic, returning `None` as soon as a column value or step overflows.
+fn fold_block_checked(rows: &[Vec<u8>], start: usize, end: usize, op: u8) -> Option<u128> {
+    let mut block_acc: u128 = if op == b'+' { 0 } else { 1 };
+    for c in start..end {
+        let mut digits = rows.iter().map(|r| r[c]).filter(u8::is_ascii_digit).peekable();
+        if digits.peek().is_none() {
+            continue;
+        }
+        let val = digits.try_fold(0u128, |v, ch| v.checked_mul(10)?.checked_add((ch - b'0') as u128))?;
+        block_acc = if op == b'+' { block_acc.checked_add(val)? } else { block_acc.checked_mul(val)? };
+    }
+    Some(block_acc)
+}
+
+/// `BigUint` fold of block `[start, end)`, the slow path for blocks that overflow `u128`.
+fn fold_block_big(rows: &[Vec<u8>], start: usize, end: usize, op: u8) -> BigUint {
+    let mut block_acc = BigUint::from(if op == b'+' { 0u8 } else { 1u8 });
+    for c in start..end {
+        let digits: Vec<u8> = rows.iter().map(|r| r[c]).filter(u8::is_ascii_digit).collect();
+        if digits.is_empty() {
+            continue;
+        }
+        let val = digits
+            .iter()
+            .fold(BigUint::from(0u8), |v, &ch| v * 10u8 + (ch - b'0'));
+        if op == b'+' {
+            block_acc += val;
+        } else {
+            block_acc *= val;
+        }
+    }
+    block_acc
+}
+
+/// Evaluates every part2 block left-to-right, returning its operator and result.
+/// 
+/// Panics if a block overflows `u128`; `process_input_part2_exact` handles those.
+fn evaluate_part2_blocks(input: &str, opts: &GridOptions) -> Vec<(u8, u128)> {
+    let (rows, blocks) = part2_layout(input, opts);
+    
+    let mut results = Vec::with_capacity(blocks.len());
+
+    for (op, start, end) in blocks {
+        eprintln!("Block [{}, {}): op={}", start, end, op as char);
+
+        // Same u128 fold as `process_input_part2_exact`'s fast path
+        let block_acc = fold_block_checked(&rows, start, end, op)
+            .unwrap_or_else(|| panic!("block [{start}, {end}) overflows u128; use --exact"));
+
+        eprintln!("  block_acc={}", block_acc);
+        results.push((op, block_acc));
+    }
+
+    results
+}
+
+/// One part2 block: `(operator, start, end)` for its `[start, end)` column range.
+type Block = (u8, usize, usize);
+
+/// Pads the part2 grid and splits it into blocks.
+/// 
+/// Returns the padded number rows and the blocks left-to-right.
+fn part2_layout(input: &str, opts: &GridOptions) -> (Vec<Vec<u8>>, Vec<Block>) {
+    // Whitespace-only rows are dropped: with no digits and no operator they
+    // add no operand digits and can't turn a separator column into a block one.
+    let expanded: Vec<Cow<str>> = non_empty_lines(input)
+        .into_iter()
+        .map(|l| expand_tabs(l, opts.tab_width))
+        .collect();
+    let mut lines: Vec<&str> = expanded.iter().map(|l| l.as_ref()).collect();
     assert!(lines.len() >= 2, "need number rows + operator row");
 
     let op_line = lines.pop().unwrap();
@@ -178,92 +474,105 @@ fn process_input_part2(input: &str) -> u128 {
     };
 
     // Split into contiguous non-seprator blocks [start, end)
-    let blocks = split_blocks(width, is_sep);
-    
-    let mut total = 0;
-
-    for (start, end) in blocks {
-        // find operator within this block
-        let mut op: u8 = 0;
-        for c in start..end {
-            let ch = op_row[c];
-            if ch == b'+' || ch == b'*' {
-                op = ch;
-                break;
-            }
-        }
-        assert!(op == b'+' || op == b'*', "no operator in block");
+    let blocks = split_blocks(width, is_sep)
+        .into_iter()
+        .map(|(start, end)| {
+            // find operator within this block
+            let op = op_row[start..end]
+                .iter()
+                .copied()
+                .find(|&ch| ch == b'+' || ch == b'*')
+                .expect("no operator in block");
+            (op, start, end)
+        })
+        .collect();
 
-        //fold operands on the fly (avoid storing operands Vec)
-        let mut block_acc: u128 = if op == b'+' { 0 } else { 1 };
-        
-        eprintln!("Block [{}, {}): op={}", start, end, op as char);
+    (rows, blocks)
+}
 
-        for c in start..end {
-            // Build number from digits in this column, top->bottom, skipping spaces
-            let mut have_digit = false;
-            let mut val = 0;
-
-            for r in &rows {
-                let ch = r[c];
-                if ch.is_ascii_digit() {
-                    have_digit = true;
-                    val = val * 10 + (ch - b'0') as u128;
-                } else {
-                    // Only spaces expected in the grid area
-                    debug_assert!(ch== b' ')
-                }
-            }
-            if have_digit {
-                eprintln!("  col {}: val={}", c, val);
-                if op == b'+' {
-                    block_acc += val;
-                } else {
-                    block_acc *= val;
-                    
-                }
-            }
-        }
-        eprintln!("  block_acc={}", block_acc);
-        total += block_acc;
+/// Parses the `--delimiter` flag value into a `Separator`.
+/// 
+/// Accepts a single character (e.g. `,`) or the words `whitespace`/`ws`.
+fn parse_separator(arg: &str) -> Result<Separator, String> {
+    match arg {
+        "whitespace" | "ws" => Ok(Separator::Whitespace),
+        "\\t" | "tab" => Ok(Separator::Byte(b'\t')),
+        s if s.len() == 1 => Ok(Separator::Byte(s.as_bytes()[0])),
+        _ => Err(format!("Invalid delimiter '{arg}'. Use a single character or 'whitespace'.")),
     }
-
-    total
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let mut args = std::env::args().skip(1);
-    let mode = args.next().unwrap_or_else(|| "part1".to_string());
-
-    let session = env::var("AOC_SESSION")
-        .map_err(|_| "AOC_SESSION environment variable is not set")?;
+    let mut mode = "part1".to_string();
+    let mut sep = Separator::Whitespace;
+    let mut opts = GridOptions::default();
+    let mut raw = false;
+    let mut exact = false;
+    let mut strict = false;
 
-    let body = ureq::get(INPUT_URL)
-        .header("Cookie", &format!("session={session}"))
-        .call()?
-        .into_body()
-        .read_to_string()?;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--delimiter" {
+            let value = args.next().ok_or("--delimiter requires a value")?;
+            sep = parse_separator(&value)?;
+        } else if let Some(value) = arg.strip_prefix("--delimiter=") {
+            sep = parse_separator(value)?;
+        } else if arg == "--raw" {
+            raw = true;
+        } else if arg == "--exact" {
+            exact = true;
+        } else if arg == "--strict" {
+            strict = true;
+        } else if arg == "--tab-width" {
+            let value = args.next().ok_or("--tab-width requires a value")?;
+            opts.tab_width = value.parse()?;
+        } else if arg == "--max-width" {
+            let value = args.next().ok_or("--max-width requires a value")?;
+            opts.max_width = Some(value.parse()?);
+        } else {
+            mode = arg;
+        }
+    }
 
-   match mode.as_str() {
+    with_input(6, |body| match mode.as_str() {
+        "part1" | "1" if strict => {
+           if sep != Separator::Whitespace {
+               return Err("--strict only supports whitespace-separated rows".into());
+           }
+           let grand_total = process_input_part1_strict(body)?;
+           write_answer(&mut io::stdout().lock(), "Part1: Grand total is", grand_total, raw)?;
+           Ok(())
+        },
         "part1" | "1" => {
-           let grand_total = process_input_part1(&body);
-           println!("Part1: Grand total is: {}", grand_total);
+           let grand_total = process_input_part1(body, sep);
+           write_answer(&mut io::stdout().lock(), "Part1: Grand total is", grand_total, raw)?;
+           Ok(())
+        },
+        "part2" | "2" if exact => {
+           check_max_width(body, &opts)?;
+           let grand_total = process_input_part2_exact(body, &opts);
+           write_answer(&mut io::stdout().lock(), "Part2: Grand total is", grand_total, raw)?;
            Ok(())
         },
         "part2" | "2" => {
-           let grand_total = process_input_part2(&body);
-           println!("Part2: Grand total is: {}", grand_total);
+           let grand_total = process_input_part2_checked(body, &opts)?;
+           write_answer(&mut io::stdout().lock(), "Part2: Grand total is", grand_total, raw)?;
+           Ok(())
+        },
+        "dominant" => {
+           check_max_width(body, &opts)?;
+           let (index, op, value) = dominant_block(body, &opts).ok_or("no part2 blocks found")?;
+           let block = format!("block {index} ({}) = {value}", op as char);
+           write_answer(&mut io::stdout().lock(), "Dominant block", block, raw)?;
            Ok(())
         },
         _ => {
-            return Err(format!(
-                "Invalid mode '{mode}'. Use 'part1' or 'part2'."
+            Err(format!(
+                "Invalid mode '{mode}'. Use 'part1', 'part2' or 'dominant'."
             )
             .into())
-
         }
-    }   
-   
+    })
 }
 
 #[cfg(test)]
@@ -278,7 +587,83 @@ mod tests {
 6 98  215 314
 *   +   *   +
 ";
-        assert_eq!(process_input_part1(input), 4277556)
+        assert_eq!(process_input_part1(input, Separator::Whitespace), 4277556)
+    }
+
+    #[test]
+    fn part1_part2_agree_on_single_digit_columns() {
+        // With one operand per column and no multi-digit columns, part2's vertical
+        // block reading degenerates to part1's column reading.
+        let inputs = [
+            "1 2 3\n+ * +\n",
+            "7\n*\n",
+            "9 8 7 6 5\n* * + + *\n",
+            "  4   5\n  +   *\n",
+            "3 0\n* +",
+        ];
+        for input in inputs {
+            assert_eq!(
+                process_input_part1(input, Separator::Whitespace),
+                process_input_part2(input, &GridOptions::default()),
+                "part1/part2 diverge on {input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn part1_comma_delimited() {
+        let input = "\
+123,328,51,64
+45,64,387,23
+6,98,215,314
+*,+,*,+
+";
+        assert_eq!(process_input_part1(input, Separator::Byte(b',')), 4277556)
+    }
+
+    #[test]
+    fn comma_tokenizers_split_on_delimiter() {
+        let mut nums = Vec::new();
+        parse_u128_sep(b"1, 2,3", Separator::Byte(b','), &mut nums);
+        assert_eq!(nums, vec![1, 2, 3]);
+        assert_eq!(parse_ops_tokens("* , +,*", Separator::Byte(b',')), vec![b'*', b'+', b'*']);
+    }
+
+    #[test]
+    fn parse_ints_reports_offending_token() {
+        assert_eq!(parse_ints::<u64>(" 123  328\t51 64 "), Ok(vec![123, 328, 51, 64]));
+        assert_eq!(parse_ints::<i64>("-5 0 17"), Ok(vec![-5, 0, 17]));
+        assert_eq!(parse_ints::<u64>(""), Ok(vec![]));
+
+        let err = parse_ints::<u64>("1 2 -3 4").unwrap_err();
+        assert_eq!(err, ParseIntsError { index: 2, token: "-3".to_string() });
+        assert_eq!(err.to_string(), "invalid integer '-3' at token 2");
+        assert!(parse_ints::<i64>("7 x8").is_err());
+    }
+
+    #[test]
+    fn part1_strict_reports_source_line() {
+        let input = "\
+123 328  51 64
+45 64  387 23
+6 98  215 314
+*   +   *   +
+";
+        assert_eq!(process_input_part1_strict(input), Ok(4277556));
+
+        let bad_token = "\n1 2\n3 x4\n* +\n";
+        assert_eq!(
+            process_input_part1_strict(bad_token),
+            Err("line 3: invalid integer 'x4' at token 1".to_string())
+        );
+        assert_eq!(
+            process_input_part1_strict("1 2\n3\n* +\n"),
+            Err("line 2 has 1 numbers but operator row has 2".to_string())
+        );
+        assert_eq!(
+            process_input_part1_strict("1 2\n* -\n"),
+            Err("line 2: invalid operator '-' at token 1".to_string())
+        );
     }
 
     #[test]
@@ -289,6 +674,82 @@ mod tests {
   6  98   215  314
   *   +     *    +
 ";
-        assert_eq!(process_input_part2(input), 3263827)
+        assert_eq!(process_input_part2(input, &GridOptions::default()), 3263827)
+    }
+
+    #[test]
+    fn part2_max_width_rejects_overlong_line() {
+        // The blank line still counts towards the reported line number
+        let input = "12 3\n\n4  56789\n+  *\n";
+
+        let opts = GridOptions { max_width: Some(4), ..GridOptions::default() };
+        assert_eq!(
+            process_input_part2_checked(input, &opts),
+            Err("line 3 is 8 columns wide, exceeding max width 4".to_string())
+        );
+
+        let opts = GridOptions { max_width: Some(8), ..GridOptions::default() };
+        assert_eq!(
+            process_input_part2_checked(input, &opts),
+            Ok(process_input_part2(input, &GridOptions::default()))
+        );
+    }
+
+    #[test]
+    fn part2_exact_retries_overflowing_block() {
+        // Block 2 is twenty columns of 99 multiplied: 99^20 ~ 8.2e39 > u128::MAX
+        let nines = "9".repeat(20);
+        let input = format!("12 {nines} 3\n45 {nines} 4\n+  *{} *\n", " ".repeat(19));
+
+        let (rows, blocks) = part2_layout(&input, &GridOptions::default());
+        let (op, start, end) = blocks[1];
+        assert_eq!(fold_block_checked(&rows, start, end, op), None);
+
+        let expected = BigUint::from(14u8 + 25) + BigUint::from(99u8).pow(20) + BigUint::from(34u8);
+        assert_eq!(process_input_part2_exact(&input, &GridOptions::default()), expected);
+
+        let sample = "123  328   51   64\n 45  64   387   23\n  6  98   215  314\n  *   +     *    +\n";
+        assert_eq!(process_input_part2_exact(sample, &GridOptions::default()), BigUint::from(3263827u32));
+    }
+
+    #[test]
+    fn dominant_block_on_part2_sample() {
+       let input = "\
+123  328   51   64
+ 45  64   387   23
+  6  98   215  314
+  *   +     *    +
+";
+        assert_eq!(dominant_block(input, &GridOptions::default()), Some((2, b'*', 3253600)));
+
+        // Equal results: the leftmost block wins
+        assert_eq!(dominant_block("12 21\n*  *\n", &GridOptions::default()), Some((0, b'*', 2)));
+    }
+
+    #[test]
+    fn part2_tab_indented_matches_space_aligned() {
+        // Same layout as the sample with each 4-column block starting on a tab stop
+        let spaces = "\
+123 328  51 64 
+ 45 64  387 23 
+  6 98  215 314
+*   +   *   +  
+";
+        let tabs = "123\t328\t 51\t64\n 45\t64\t387\t23\n  6\t98\t215\t314\n*\t+\t*\t+\n";
+        let opts = GridOptions { tab_width: 4, ..GridOptions::default() };
+        assert_eq!(expand_tabs("1\t22\t", 4), "1   22  ");
+        assert_eq!(
+            process_input_part2(tabs, &opts),
+            process_input_part2(spaces, &GridOptions::default())
+        );
+        assert_eq!(process_input_part2(tabs, &opts), 3263827);
+    }
+
+    #[test]
+    fn part2_ignores_blank_rows() {
+        let input = "\n123 328\n                \n 45 64 \n*   +  \n\n";
+        let compact = "123 328\n 45 64 \n*   +  \n";
+        assert_eq!(part2_layout(input, &GridOptions::default()), part2_layout(compact, &GridOptions::default()));
+        assert_eq!(process_input_part2(input, &GridOptions::default()), 840 + 68);
     }
 }
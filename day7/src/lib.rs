@@ -0,0 +1,1272 @@
+//! Day 7 beam/timeline grid solver as a library.
+//!
+//! Parsing (`parse_grid`, `Grid`) and the DP entry points (`process_part1_int`,
+//! `process_part2_int`, ...) are public so callers can parse a grid once and
+//! reuse it across many runs; `main.rs` only fetches input and prints answers.
+
+use anyhow::{bail, Context, Result};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use std::time::{Duration, Instant};
+
+/// Parsed grid representation.
+/// 
+/// rows: Vec<Vecv<u8>> where each row is a byte slice of '.' '^' 'S'
+/// width: fixed width, all rows are padded/validated to this width
+#[derive(Debug, Clone)]
+pub struct Grid {
+    pub rows: Vec<Vec<u8>>,
+    pub width: usize,
+}
+
+/// Parse input text into a rectangular grid.
+/// 
+/// Steps:
+/// 1) Keep non-empty lines.
+/// 2) Validate all lines have the same width (AoC grids are rectangular).
+/// 3) Store each line as bytes for fast indexing (no UTF-8 surprises).
+/// 
+pub fn parse_grid(input: &str) -> Result<Grid> {
+    parse_grid_with(input, 0)
+}
+
+/// `parse_grid` that first drops lines shorter than `min_len` bytes.
+/// 
+/// A stray partial line (e.g. a truncated last line) is skipped instead of
+/// failing the ragged-grid check; rows of at least `min_len` must still agree
+/// on width. `min_len` 0 (or 1) keeps every non-empty line, like `parse_grid`.
+pub fn parse_grid_with(input: &str, min_len: usize) -> Result<Grid> {
+    // Keep each line's 1-based number in the input for error messages
+    let lines: Vec<(usize, &str)> = input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty() && l.len() >= min_len)
+        .map(|(i, l)| (i + 1, l))
+        .collect();
+    if lines.is_empty() {
+        bail!("Empty input");
+    }
+
+    let width = lines[0].1.len();
+    if width == 0 {
+        bail!("First line is empty");
+    }
+
+    let mut rows = Vec::with_capacity(lines.len());
+    for &(line_no, line) in &lines {
+        if line.len() != width {
+            bail!(
+                "Ragged grid: line {line_no} has length {}, expected {width}",
+                line.len()
+            );
+        }
+        rows.push(line.as_bytes().to_vec());
+    }
+
+    Ok(Grid {rows, width })
+}
+
+/// Trims empty border columns and trailing rows that cannot affect the answers.
+/// 
+/// Returns the cropped grid and the `(row, col)` offset of its top-left cell in
+/// the original grid; the start column translates as `s_col - col`.
+/// 
+/// Rules:
+/// - The top row holds 'S' and is always kept, so the row offset is always 0.
+/// - Rows below the last '^' only carry beams straight down and are dropped.
+/// - Columns keep a 1-column margin around every '^' and 'S': a split sends beams
+///   one column sideways, and those beams must not fall off a cropped edge.
+pub fn crop_to_content(grid: &Grid) -> (Grid, (usize, usize)) {
+    let is_content = |c: u8| c == b'^' || c == b'S';
+
+    let bottom = grid
+        .rows
+        .iter()
+        .rposition(|row| row.contains(&b'^'))
+        .unwrap_or(0);
+    let rows = &grid.rows[..=bottom];
+
+    let left = rows
+        .iter()
+        .filter_map(|row| row.iter().position(|&c| is_content(c)))
+        .min();
+    let right = rows
+        .iter()
+        .filter_map(|row| row.iter().rposition(|&c| is_content(c)))
+        .max();
+
+    let (left, right) = match (left, right) {
+        (Some(l), Some(r)) => (l.saturating_sub(1), (r + 1).min(grid.width - 1)),
+        _ => (0, grid.width - 1),
+    };
+
+    let cropped = Grid {
+        rows: rows.iter().map(|row| row[left..=right].to_vec()).collect(),
+        width: right - left + 1,
+    };
+    (cropped, (0, left))
+}
+
+/// Find the column of 'S' in the top row.
+/// 
+/// Steps:
+/// 1) Scan the row for byte 'S'.
+/// 2) REturn its index, or error if missing.
+pub fn find_start_column(top_row: &[u8]) -> Result<usize> {
+    top_row
+        .iter()
+        .position(|&c| c == b'S')
+        .with_context(|| "No 'S' found in top row")
+}
+
+/// Build splitter masks for all rows.
+/// 
+/// Each row becomes a bitset (Vec<u64>) where:
+/// - bit c = 1 if grid[row][c] == '^'
+/// 
+/// Steps per row:
+/// 1) Create zeroed u64 chunks.
+/// 2) For each column with '^', set the corresponding bit.
+/// 3) Mask last chunk to clear unused bits.
+fn build_split_masks(rows: &[Vec<u8>], width: usize, chunks: usize, last_mask: u64) -> Vec<Vec<u64>> {
+    let mut out = Vec::with_capacity(rows.len());
+
+    for row in rows {
+        let mut mask_row = vec![0u64; chunks];
+
+        for c in 0..width {
+            if row[c] == b'^' {
+                mask_row[c / 64] |= 1u64 << (c % 64);
+            }
+        }
+
+        // Ensure unused bits are always 0 (important after shifts/or).
+        if let Some(last) = mask_row.last_mut() {
+            *last &= last_mask;
+        }
+
+        out.push(mask_row);
+    }
+
+    out
+}
+
+/// Perform one DP step: propagate beams from `cur` into `next` for a specific row,
+/// and count how many splitters are hit.
+/// 
+/// Inputs:
+/// - cur: current beam bitset (row r-1)
+/// - split: bitset of '^' positions for row r
+/// - next output bitset for row r (overwritten)
+/// - last_mask: masks unused tail bits (width not multiple of 64)
+/// 
+/// Output:
+/// - number of split events on this row (popcount of hit splitters)
+/// 
+/// Algorithm:
+/// 1) hit = cur & split
+/// 2) straight = cur & !split
+/// 3) next = straight
+/// 4) next |= (hit << 1)   // split right
+/// 5) next |= (hit >> 1)   // split left
+/// 6) next[last] &= last_mask
+/// 7) return popcount(hit)
+fn step_row_part1(cur: &[u64], split: &[u64], next: &mut [u64], last_mask: u64) -> u64 {
+    debug_assert_eq!(cur.len(), split.len());
+    debug_assert_eq!(cur.len(), next.len());
+
+    let chunks = cur.len();
+    next.fill(0);
+
+    // Pass 1: compute hit + straight, write straight into next, count splits.
+    let mut splits_on_row: u64 = 0;
+    for k in 0..chunks {
+        let hit = cur[k] & split[k];
+        let straight = cur[k] & !split[k];
+        next[k] = straight; 
+        splits_on_row += hit.count_ones() as u64
+    }
+
+    // Pass 2: OR in right-shifted split beams: (hit << 1)
+    //
+    // We recompute `hit` (cur & split) to avoid allocating a temporary `hit` vector.
+    // This is still 0(chunks) and typically faster than heap traffic.
+    let mut carry: u64 = 0;
+    for k in 0..chunks {
+        let hit = cur[k] & split[k];
+        let new_carry = hit >> 63;      // MSB spills into next chunk as LSB
+        let shifted = (hit << 1) | carry;   // carry comes from previous chunk
+        next[k] |= shifted;
+        carry = new_carry
+    }
+
+    // Pass 3: OR in left-shifted split beams: (hit >> 1)
+    let mut carry: u64 = 0;
+    for k in (0..chunks).rev() {
+        let hit = cur[k] & split[k];
+        let new_carry = hit & 1;        // LSB spills into previous chunk as MSB
+        let shifted = (hit >> 1) | (carry << 63);
+        next[k] |= shifted;
+        carry = new_carry;
+    }
+
+    // Clear unused tail bits (so they never leak and cause false hits).
+    if let Some(last) = next.last_mut() {
+        *last &= last_mask;
+    }
+
+    splits_on_row
+}
+
+/// Bitset layout for a grid of width `w`: returns `(chunks, last_mask)`.
+/// 
+/// - one u64 = 64 columns
+/// - chunks = ceil(w / 64)
+/// - last chunk may have unused bits if w is not multiple of 64;
+///   last_mask keeps only valid column bits (lower bits).
+fn bitset_layout(w: usize) -> (usize, u64) {
+    let chunks = w.div_ceil(64);
+    let last_mask: u64 = if w.is_multiple_of(64) {
+        !0u64
+    } else {
+        (1u64 << (w % 64)) - 1
+    };
+    (chunks, last_mask)
+}
+
+/// Set a single beam bit in a bitset at column `col`.
+#[inline]
+fn set_bit(bits: &mut [u64], col: usize) {
+    bits[col / 64] |= 1u64 << (col % 64);
+}
+
+/// Whether the beam bit at column `col` is set.
+#[inline]
+fn has_bit(bits: &[u64], col: usize) -> bool {
+    bits[col / 64] >> (col % 64) & 1 == 1
+}
+
+/// Number of beams (set bits) in a bitset.
+#[inline]
+fn count_beams(bits: &[u64]) -> u64 {
+    bits.iter().map(|c| c.count_ones() as u64).sum()
+}
+
+/// The part1 descent from row 0, shared by the part1 metrics.
+/// 
+/// Calls `f(row, splits, beams)` once per grid row: row 0 reports the start
+/// beam with no splits, every later row its `step_row_part1` split count and
+/// the bitset of beams leaving it.
+fn walk_part1(grid: &Grid, s_col: usize, mut f: impl FnMut(usize, u64, &[u64])) {
+    let (chunks, last_mask) = bitset_layout(grid.width);
+    let split_masks = build_split_masks(&grid.rows, grid.width, chunks, last_mask);
+
+    let mut cur = vec![0u64; chunks];
+    let mut next = vec![0u64; chunks];
+    set_bit(&mut cur, s_col);
+    f(0, 0, &cur);
+
+    for (row, split) in split_masks.iter().enumerate().skip(1) {
+        let splits = step_row_part1(&cur, split, &mut next, last_mask);
+        std::mem::swap(&mut cur, &mut next);
+        f(row, splits, &cur);
+    }
+}
+
+/// Split beams sent off the (left, right) edge on `row`.
+/// 
+/// `entering` is the beam bitset leaving the row above; a beam in an edge
+/// column that hits a splitter there loses its outward half.
+fn edge_losses(grid: &Grid, row: usize, entering: &[u64]) -> (u64, u64) {
+    let last = grid.width - 1;
+    let lost = |col: usize| (has_bit(entering, col) && grid.rows[row][col] == b'^') as u64;
+    (lost(0), lost(last))
+}
+
+/// Process part1 input
+/// 
+/// High level abstract steps:
+/// 1) Parse the grid into rows of bytes.
+/// 2) Find the start column 'S' in the top row.
+/// 3) Precompute splitter masks: for each row, a bitset with 1s where '^' exists.
+/// 4) Run a row-by-row bitset DP that updates beam positions and counts splitter hits.
+pub fn process_part1_int(grid: &Grid, s_col: usize) -> u64 {
+    process_part1_from_row(grid, 0, s_col)
+}
+
+/// `process_part1_int` with the beam entering at (`start_row`, `s_col`).
+/// 
+/// Rows above `start_row` are ignored; the DP seeds the beam there and
+/// propagates from row `start_row + 1` down.
+pub fn process_part1_from_row(grid: &Grid, start_row: usize, s_col: usize) -> u64 {
+
+    let (h, w) = (grid.rows.len(), grid.width);
+
+    if start_row + 1 >= h {
+        return 0;
+    }
+
+    let (chunks, last_mask) = bitset_layout(w);
+
+    // Precompute: split_masks[r][k] has bit=1 if grid[r][col] == '^'.
+    let split_masks = build_split_masks(&grid.rows, w, chunks, last_mask);
+
+    // Beam state:
+    // cur: bitset for current row
+    // next: bitset for next row
+    let mut cur = vec![0u64; chunks];
+    let mut next = vec![0u64; chunks];
+
+    // Initialize beam "presence" at the start row, column S.
+    set_bit(&mut cur, s_col);
+
+    let mut splits_total: u64 = 0;
+
+    // We start below the start row, which holds 'S' (row 0 by default).
+    // The beam enters the next row from there.
+    for split in &split_masks[start_row + 1..h] {
+        // Compute next row's beam bitset and number of splits on this row.
+        let  splits_on_row = step_row_part1(&cur, split, &mut next, last_mask);
+
+        splits_total += splits_on_row;
+        std::mem::swap(&mut cur, &mut next);
+    }
+
+    splits_total
+}
+
+/// One-time-use variant of `step_row_part1`: splitters are consumed once hit.
+/// 
+/// Semantics:
+/// - Within a row, beams are a set (one bit per column), so every beam reaching a
+///   splitter on that row is the same beam: the splitter fires once and is counted once.
+/// - Across rows/descents, a consumed splitter's bit is cleared in `split`, so any
+///   later beam reaching that cell passes straight through as if it were '.'.
+/// 
+/// Returns the number of splitters consumed on this row.
+fn step_row_part1_consume(cur: &[u64], split: &mut [u64], next: &mut [u64], last_mask: u64) -> u64 {
+    let splits_on_row = step_row_part1(cur, split, next, last_mask);
+
+    // Consume every splitter that was hit on this row.
+    for (s, &c) in split.iter_mut().zip(cur) {
+        *s &= !c;
+    }
+
+    splits_on_row
+}
+
+/// Part1 with one-time-use splitters, for beams entering at each of `s_cols` in turn.
+/// 
+/// All descents share one mutable copy of the split masks, so a splitter consumed
+/// by an earlier beam lets later beams pass straight through it.
+/// Returns the total number of splits across all descents.
+/// 
+/// A single descent steps each row once, so it can never reach a splitter it
+/// already consumed: with one `s_col` this equals `process_part1_int`, and
+/// consumption only changes the total across several `s_cols`.
+pub fn process_part1_one_time_int(grid: &Grid, s_cols: &[usize]) -> u64 {
+    let (h, w) = (grid.rows.len(), grid.width);
+
+    if h <= 1 {
+        return 0;
+    }
+
+    let (chunks, last_mask) = bitset_layout(w);
+
+    // Mutable copy: consumed splitters are cleared here, the grid stays untouched.
+    let mut split_masks = build_split_masks(&grid.rows, w, chunks, last_mask);
+
+    let mut cur = vec![0u64; chunks];
+    let mut next = vec![0u64; chunks];
+
+    let mut splits_total: u64 = 0;
+
+    for &s_col in s_cols {
+        cur.fill(0);
+        set_bit(&mut cur, s_col);
+
+        for split in &mut split_masks[1..h] {
+            splits_total += step_row_part1_consume(&cur, split, &mut next, last_mask);
+            std::mem::swap(&mut cur, &mut next);
+        }
+    }
+
+    splits_total
+}
+
+/// Debug dump of the part1 beam bitset, one string per row.
+/// 
+/// Row r shows the beams leaving row r (after its splitters), column 0 first,
+/// as '1'/'0'; row 0 is just the start beam. Meant for eyeballing the
+/// shift/carry logic in `step_row_part1` on narrow grids.
+pub fn dump_beams(grid: &Grid, s_col: usize) -> Vec<String> {
+    let render = |bits: &[u64]| -> String {
+        (0..grid.width)
+            .map(|col| if has_bit(bits, col) { '1' } else { '0' })
+            .collect()
+    };
+
+    let mut dump = Vec::with_capacity(grid.rows.len());
+    walk_part1(grid, s_col, |_, _, beams| dump.push(render(beams)));
+    dump
+}
+
+/// Peak beam width of the part1 descent.
+/// 
+/// Walks the same descent as `process_part1_int` and returns the largest
+/// number of distinct beam columns seen in any row (at least 1, for the
+/// beam entering at `s_col`).
+pub fn max_simultaneous_beams(grid: &Grid, s_col: usize) -> u64 {
+    let mut peak: u64 = 0;
+    walk_part1(grid, s_col, |_, _, beams| peak = peak.max(count_beams(beams)));
+    peak
+}
+
+/// Number of rows on which at least one part1 split occurred.
+/// 
+/// Walks the same descent as `process_part1_int` and counts the rows whose
+/// `step_row_part1` split count is nonzero, so several splits on one row
+/// count once.
+pub fn rows_with_splits(grid: &Grid, s_col: usize) -> usize {
+    let mut rows = 0;
+    walk_part1(grid, s_col, |_, splits, _| {
+        if splits > 0 {
+            rows += 1;
+        }
+    });
+    rows
+}
+
+/// Part1 variant that counts split beams per side instead of total splits.
+/// 
+/// Every splitter hit emits one beam to the left and one to the right, but a beam
+/// sent off the grid (left of column 0, right of column w-1) is lost.
+/// Returns `(left_in_bounds, right_in_bounds)` summed over the whole descent.
+pub fn process_part1_sides_int(grid: &Grid, s_col: usize) -> (u64, u64) {
+    let (mut left, mut right) = (0u64, 0u64);
+    let mut entering: Vec<u64> = Vec::new();
+
+    walk_part1(grid, s_col, |row, splits, beams| {
+        if row > 0 {
+            let (lost_left, lost_right) = edge_losses(grid, row, &entering);
+            left += splits - lost_left;
+            right += splits - lost_right;
+        }
+        entering.clear();
+        entering.extend_from_slice(beams);
+    });
+
+    (left, right)
+}
+
+/// Where part1 beams end up, alongside the usual split count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Part1Report {
+    /// Splitter hits (the part1 answer)
+    pub splits: u64,
+    /// Split beams sent off the grid past column 0 or column w-1
+    pub side_exits: u64,
+    /// Distinct beams leaving through the bottom row
+    pub bottom_reaches: u64,
+}
+
+impl Part1Report {
+    /// Every way a beam leaves the grid: sideways or through the bottom.
+    pub fn total_exits(&self) -> u64 {
+        self.side_exits + self.bottom_reaches
+    }
+}
+
+/// Part1 run that also treats the grid bottom as a sink.
+/// 
+/// Side exits are counted as in `process_part1_sides_int` (a split at an edge
+/// column loses one beam); bottom reaches are the beams still present after
+/// the last row. Without merges, `total_exits() == 1 + splits`: the start beam
+/// plus one extra beam per split.
+pub fn process_part1_report_int(grid: &Grid, s_col: usize) -> Part1Report {
+    let mut report = Part1Report { splits: 0, side_exits: 0, bottom_reaches: 0 };
+    let mut entering: Vec<u64> = Vec::new();
+
+    walk_part1(grid, s_col, |row, splits, beams| {
+        if row > 0 {
+            let (lost_left, lost_right) = edge_losses(grid, row, &entering);
+            report.splits += splits;
+            report.side_exits += lost_left + lost_right;
+        }
+        // Whatever leaves the last row reaches the bottom
+        report.bottom_reaches = count_beams(beams);
+        entering.clear();
+        entering.extend_from_slice(beams);
+    });
+
+    report
+}
+
+/// Parse input and run `process_part1_report_int`.
+pub fn process_part1_report(input: &str) -> Result<Part1Report> {
+    let grid = parse_grid(input)?;
+    let s_col = find_start_column(&grid.rows[0])?;
+    Ok(process_part1_report_int(&grid, s_col))
+}
+
+pub fn process_part1(input: &str) -> Result<u64> {
+    let grid = parse_grid(input)?;
+    let s_col = find_start_column(&grid.rows[0])?;
+    Ok(process_part1_int(&grid, s_col))
+}
+
+/// One DP step for Part2
+///
+/// Inputs:
+/// - row: current grid row bytes
+/// - cur: current timelines per column (active in [l..r])
+/// - next: output timelines per column (will be cleared/filled only in needed range)
+/// - l, r: active window in cur
+/// 
+/// Returns:
+/// - (new_l, new_r): active window in `next` after propagation
+/// 
+/// Part2 counts distinct timelines (paths).
+/// Timelines do NOT merge, even if they end at the same cell.
+/// DP state cur[c] = number of timelines arriving at column c for the current row.
+/// On '.' : next[c]  += cur[c]
+/// On '^' : next[c-1] += cur[c] (if in bounds)
+///          next[c+1] += cur[c] (if in bounds)
+/// Answer: sum(cur) at the bottom row.
+/// 
+/// Using BigUint because values can be huge.
+/// 
+/// Optimization: track active window [l..r] where cur[c] != 0 so we avoid full width
+fn step_row_part2(row: &[u8], cur: &[BigUint], next: &mut [BigUint], l: usize, r: usize) -> (usize, usize) {
+    let w = cur.len();
+    debug_assert_eq!(row.len(), w);
+    debug_assert_eq!(next.len(), w);
+    debug_assert!(l <= r && r < w);
+
+    // Next activity can expand by at most 1 to each side
+    let nl = l.saturating_sub(1);
+    let nr = (r + 1).min(w - 1);
+
+    // Clear only the region that might be written.
+    for v in &mut next[nl..=nr] {
+        v.set_zero();
+    }
+
+    // Propagate counts.
+    for c in l..=r {
+        if cur[c].is_zero() {
+            continue;
+        }
+
+        if row[c] == b'^' {
+            if c > 0 {
+                next[c - 1] += &cur[c];
+            }
+            if c + 1 < w {
+                next[c + 1] += &cur[c];
+            }
+        } else {
+            next[c] += &cur[c];
+        }
+    }
+
+    //Compute new active window in [nl..nr]
+    let mut new_l = nl;
+    while new_l <= nr && next[new_l].is_zero() {
+        new_l += 1;
+    }
+    if new_l > nr {
+        // No timelines survived (everything fell off the edges).
+        return (0, 0);
+    }
+
+    let mut new_r = nr;
+    while next[new_r].is_zero() {
+        new_r -= 1;
+    }
+
+    (new_l, new_r)
+}
+
+/// Internal Part2. Returns total number of timlines as BigUint
+pub fn process_part2_int(grid: &Grid, s_col: usize) -> BigUint {
+    process_part2_from_row(grid, 0, s_col)
+}
+
+/// `process_part2_int` with the beam entering at (`start_row`, `s_col`);
+/// rows above `start_row` are ignored.
+pub fn process_part2_from_row(grid: &Grid, start_row: usize, s_col: usize) -> BigUint {
+    let (cur, l, r) = part2_bottom_row(grid, start_row, s_col);
+
+    // Total timelines is the sum at the final row.
+    let mut total = BigUint::zero();
+    for v in &cur[l..=r] {
+        total += v;
+    }
+
+    total
+
+}
+
+/// Part2 timeline counts per column of the bottom row, for each column that has any.
+/// 
+/// These are the values `process_part2_int` sums, so the counts add up to its total.
+/// Columns are ascending.
+pub fn bottom_distribution(grid: &Grid, s_col: usize) -> Vec<(usize, BigUint)> {
+    let (cur, l, r) = part2_bottom_row(grid, 0, s_col);
+
+    (l..=r)
+        .filter(|&c| !cur[c].is_zero())
+        .map(|c| (c, cur[c].clone()))
+        .collect()
+}
+
+/// Runs the part2 DP from `start_row` down to the last row.
+/// 
+/// Returns the final `cur` counts with the `[l, r]` range holding every
+/// non-zero entry; when all timelines die out the counts are all zero.
+fn part2_bottom_row(grid: &Grid, start_row: usize, s_col: usize) -> (Vec<BigUint>, usize, usize) {
+    let (h, w) = (grid.rows.len(), grid.width);
+
+    let mut cur = vec![BigUint::zero(); w];
+    cur[s_col] = BigUint::one();
+
+    if start_row + 1 >= h {
+        return (cur, s_col, s_col); // timeline is already "done" on the start
+    }
+
+    let mut next = vec![BigUint::zero(); w];
+
+    let mut l = s_col;
+    let mut r = s_col;
+
+    for row_idx in start_row + 1..h {
+        let row = &grid.rows[row_idx];
+
+        let (new_l, new_r) = step_row_part2(row, &cur, &mut next, l, r);
+
+        if new_l == 0 && new_r == 0 && next[0].is_zero() {
+            return (vec![BigUint::zero(); w], 0, 0);
+        }
+
+        std::mem::swap(&mut cur, &mut next);
+        l = new_l;
+        r = new_r;
+    }
+
+    (cur, l, r)
+}
+
+/// Elapsed time of one row step in an instrumented run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RowTiming {
+    /// Grid row the step produced (1..h; row 0 is the 'S' header)
+    pub row: usize,
+    pub elapsed: Duration,
+}
+
+/// Instrumented `process_part1_int`: times every row of the descent.
+/// 
+/// Each lap covers one `step_row_part1` call plus the bitset swap.
+/// Returns one `RowTiming` per processed row (rows 1..h).
+pub fn part1_row_timings(grid: &Grid, s_col: usize) -> Vec<RowTiming> {
+    let mut timings = Vec::with_capacity(grid.rows.len().saturating_sub(1));
+    let mut started = Instant::now();
+
+    walk_part1(grid, s_col, |row, _, _| {
+        if row > 0 {
+            timings.push(RowTiming { row, elapsed: started.elapsed() });
+        }
+        started = Instant::now();
+    });
+
+    timings
+}
+
+/// Instrumented `process_part2_int`: times every `step_row_part2` call.
+/// 
+/// BigUint additions grow with the timeline counts, so later rows tend to
+/// dominate. Returns one `RowTiming` per processed row; like
+/// `process_part2_int`, it stops early once no timeline is left.
+pub fn part2_row_timings(grid: &Grid, s_col: usize) -> Vec<RowTiming> {
+    let (h, w) = (grid.rows.len(), grid.width);
+
+    let mut cur = vec![BigUint::zero(); w];
+    let mut next = vec![BigUint::zero(); w];
+
+    cur[s_col] = BigUint::one();
+    let mut l = s_col;
+    let mut r = s_col;
+
+    let mut timings = Vec::with_capacity(h.saturating_sub(1));
+
+    for (row_idx, row) in grid.rows.iter().enumerate().skip(1) {
+        let started = Instant::now();
+        let (new_l, new_r) = step_row_part2(row, &cur, &mut next, l, r);
+        timings.push(RowTiming { row: row_idx, elapsed: started.elapsed() });
+
+        if new_l == 0 && new_r == 0 && next[0].is_zero() {
+            break;
+        }
+
+        std::mem::swap(&mut cur, &mut next);
+        l = new_l;
+        r = new_r;
+    }
+
+    timings
+}
+
+/// The `n` slowest rows, slowest first (ties keep row order).
+pub fn slowest_rows(timings: &[RowTiming], n: usize) -> Vec<RowTiming> {
+    let mut sorted = timings.to_vec();
+    sorted.sort_by_key(|t| std::cmp::Reverse(t.elapsed));
+    sorted.truncate(n);
+    sorted
+}
+
+/// Returns true if the Part2 timeline count exceeds `bound`.
+pub fn exceeds_timeline_count(grid: &Grid, s_col: usize, bound: &BigUint) -> bool {
+    first_row_exceeding(grid, s_col, bound).is_some()
+}
+
+/// Runs the Part2 DP and returns the row after which the timeline count is known
+/// to exceed `bound`, or None if the final count does not exceed it.
+/// 
+/// On a grid at least two columns wide the count never goes down: a splitter in
+/// the first or last column sends one branch off the grid but keeps the other.
+/// So once the running sum over the active window exceeds `bound`, the final count
+/// must too, and the DP stops early instead of finishing the BigUint computation.
+/// On a one-column grid a splitter drops both branches, so only the last row decides.
+pub fn first_row_exceeding(grid: &Grid, s_col: usize, bound: &BigUint) -> Option<usize> {
+    let (h, w) = (grid.rows.len(), grid.width);
+
+    let mut cur = vec![BigUint::zero(); w];
+    let mut next = vec![BigUint::zero(); w];
+
+    cur[s_col] = BigUint::one();
+    let mut l = s_col;
+    let mut r = s_col;
+
+    for row_idx in 0..h {
+        if row_idx > 0 {
+            let (new_l, new_r) = step_row_part2(&grid.rows[row_idx], &cur, &mut next, l, r);
+
+            if new_l == 0 && new_r == 0 && next[0].is_zero() {
+                return None;
+            }
+
+            std::mem::swap(&mut cur, &mut next);
+            l = new_l;
+            r = new_r;
+        }
+
+        let running: BigUint = cur[l..=r].iter().sum();
+        if &running > bound && (w > 1 || row_idx + 1 == h) {
+            return Some(row_idx);
+        }
+    }
+
+    None
+}
+
+pub fn process_part2(input: &str) -> Result<BigUint> {
+    let grid = parse_grid(input)?;
+    let s_col = find_start_column(&grid.rows[0])?;
+    Ok(process_part2_int(&grid, s_col))
+}
+
+#[cfg(test)]
+#[path = "../../tools/test_support.rs"]
+mod test_support;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::grids_equal_ignoring_trailing_ws;
+
+     /// Helper: parse + start for tests
+    fn grid_and_start(input: &str) -> Result<(Grid, usize)> {
+        let g = parse_grid(input)?;
+        let s = find_start_column(&g.rows[0])?;
+        Ok((g, s))
+    }
+
+    /// The example grid from the puzzle prompt (part1: 21, part2: 40).
+    const PROMPT_EXAMPLE: &str = "\
+.......S.......
+...............
+.......^.......
+...............
+......^.^......
+...............
+.....^.^.^.....
+...............
+....^.^...^....
+...............
+...^.^...^.^...
+...............
+..^...^.....^..
+...............
+.^.^.^.^.^...^.
+...............
+";
+
+     // -------------------------
+    // Part 1: unit + regression
+    // -------------------------
+
+    #[test]
+    fn parse_rejects_empty() -> Result<()> {
+        let err = parse_grid("").err().context("expected error")?;
+        let _ = err; // just to silence unused warning in case you expand
+        Ok(())
+    }
+
+    #[test]
+    fn parse_rejects_ragged() -> Result<()> {
+        let input = "S..\n....\n";
+        if parse_grid(input).is_ok() {
+            bail!("expected ragged grid to fail");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn parse_skips_short_trailing_line() -> Result<()> {
+        let input = "..S..\n.....\n..^..\n.....\n..\n";
+        if parse_grid(input).is_ok() {
+            bail!("expected the short line to fail without a minimum length");
+        }
+
+        let g = parse_grid_with(input, 5)?;
+        assert_eq!((g.rows.len(), g.width), (4, 5));
+        assert_eq!(g.rows, parse_grid(&input[..input.len() - 3])?.rows);
+        assert_eq!(process_part1_int(&g, find_start_column(&g.rows[0])?), 1);
+
+        // A long row that disagrees is still ragged
+        if parse_grid_with("S..\n....\n", 3).is_ok() {
+            bail!("expected ragged grid to fail");
+        }
+
+        // Errors name the input line, counting the blank and skipped ones
+        let err = parse_grid_with("S..\n\n.\n....\n", 2).unwrap_err();
+        assert_eq!(err.to_string(), "Ragged grid: line 4 has length 4, expected 3");
+        Ok(())
+    }
+
+    #[test]
+    fn start_must_exist() -> Result<()> {
+        let input = "....\n.^..\n";
+        let g = parse_grid(input)?;
+        if find_start_column(&g.rows[0]).is_ok() {
+            bail!("expected missing S to fail");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn part1_tiny_single_split() -> Result<()> {
+        // r0: S at col 2
+        // r1: ^ at col 2 => hit 1
+        // r2: . => no more hits
+        let input = "\
+..S..
+..^..
+.....
+";
+        let (g, s) = grid_and_start(input)?;
+        let ans = process_part1_int(&g, s);
+        assert_eq!(ans, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn part1_two_splits_in_one_row() -> Result<()> {
+        // r0: S at col 2
+        // r1: ^ at col 2 => split -> beams at 1 and 3
+        // r2: ^ at col 1 and 3 => hit 2 => total 3
+        let input = "\
+..S..
+..^..
+.^.^.
+.....
+";
+        let (g, s) = grid_and_start(input)?;
+        let ans = process_part1_int(&g, s);
+        assert_eq!(ans, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn part1_example_from_prompt() -> Result<()> {
+        // IMPORTANT: no indentation in the literal.
+        let input = "\
+.......S.......
+...............
+.......^.......
+...............
+......^.^......
+...............
+.....^.^.^.....
+...............
+....^.^...^....
+...............
+...^.^...^.^...
+...............
+..^...^.....^..
+...............
+.^.^.^.^.^...^.
+...............
+";
+        let ans = process_part1(input)?;
+        assert_eq!(ans, 21);
+        Ok(())
+    }
+
+    #[test]
+    fn walk_part1_visits_every_row_once() -> Result<()> {
+        let (g, s) = grid_and_start(PROMPT_EXAMPLE)?;
+        let mut rows = Vec::new();
+        let mut splits = 0;
+        walk_part1(&g, s, |row, n, _| {
+            rows.push(row);
+            splits += n;
+        });
+        assert_eq!(rows, (0..g.rows.len()).collect::<Vec<_>>());
+        assert_eq!(splits, process_part1_int(&g, s));
+        Ok(())
+    }
+
+    #[test]
+    fn rows_with_splits_on_prompt_example() -> Result<()> {
+        // All seven splitter rows are hit; the empty rows in between never split
+        let (g, s) = grid_and_start(PROMPT_EXAMPLE)?;
+        assert_eq!(rows_with_splits(&g, s), 7);
+        Ok(())
+    }
+
+    #[test]
+    fn start_row_ignores_rows_above() -> Result<()> {
+        // From row 0 the beam splits on row 1 and hits both outer splitters on row 4;
+        // entering at the 'S' on row 2 it only meets the middle one.
+        let input = "\
+.......
+...^...
+...S...
+.......
+..^^^..
+.......
+";
+        let g = parse_grid(input)?;
+        let s = find_start_column(&g.rows[2])?;
+        assert_eq!(s, 3);
+
+        assert_eq!(process_part1_from_row(&g, 2, s), 1);
+        assert_eq!(process_part2_from_row(&g, 2, s), BigUint::from(2u32));
+        assert_eq!(process_part1_from_row(&g, 0, s), 3);
+        assert_eq!(process_part1_int(&g, s), 3);
+
+        // Entering on the last row leaves nothing to propagate
+        assert_eq!(process_part1_from_row(&g, 5, s), 0);
+        assert_eq!(process_part2_from_row(&g, 5, s), BigUint::one());
+        Ok(())
+    }
+
+    #[test]
+    fn part1_one_time_converging_beams_split_once() -> Result<()> {
+        // r1 splits S into cols 1 and 3; r2 splits those into 0,2 and 2,4.
+        // Both inner beams reach col 2 on r3 together: one beam, one split.
+        let input = "\
+..S..
+..^..
+.^.^.
+..^..
+.....
+";
+        let (g, s) = grid_and_start(input)?;
+        assert_eq!(process_part1_one_time_int(&g, &[s]), 4);
+
+        // A second beam from col 1 would split on r2 col 1 and again on r3 col 2,
+        // but the first descent consumed both, so it passes straight through.
+        assert_eq!(process_part1_int(&g, s) + process_part1_int(&g, 1), 6);
+        assert_eq!(process_part1_one_time_int(&g, &[s, 1]), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn part1_one_time_second_beam_passes_consumed_splitter() -> Result<()> {
+        // The first beam consumes the splitter at r1 col 2; a second beam entering
+        // at the same column passes straight through, so only 1 split in total.
+        let input = "\
+..S..
+..^..
+.....
+";
+        let (g, s) = grid_and_start(input)?;
+        assert_eq!(process_part1_one_time_int(&g, &[s]), 1);
+        assert_eq!(process_part1_one_time_int(&g, &[s, s]), 1);
+        Ok(())
+    }
+
+    /// Invariant helper: no bit at column >= w may be set in a row bitset.
+    fn assert_tail_bits_clear(bits: &[u64], w: usize) {
+        for col in w..bits.len() * 64 {
+            assert_eq!(bits[col / 64] >> (col % 64) & 1, 0, "tail bit {col} set for width {w}");
+        }
+    }
+
+    #[test]
+    fn step_row_part1_never_sets_tail_bits() {
+        // xorshift64: deterministic pseudo-random rows without extra dependencies
+        let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut rand = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for w in [1, 63, 64, 65, 127, 128, 129] {
+            let (chunks, last_mask) = bitset_layout(w);
+            let mut next = vec![0u64; chunks];
+
+            for _ in 0..200 {
+                let mut cur: Vec<u64> = (0..chunks).map(|_| rand()).collect();
+                let mut split: Vec<u64> = (0..chunks).map(|_| rand()).collect();
+                // Splitters always sit on the last column too, to push beams past it
+                set_bit(&mut split, w - 1);
+                set_bit(&mut cur, w - 1);
+                *cur.last_mut().unwrap() &= last_mask;
+                *split.last_mut().unwrap() &= last_mask;
+
+                step_row_part1(&cur, &split, &mut next, last_mask);
+                assert_tail_bits_clear(&next, w);
+            }
+        }
+    }
+
+    #[test]
+    fn dump_beams_shows_single_split() -> Result<()> {
+        let (g, s) = grid_and_start("..S..\n.....\n..^..\n.....\n")?;
+        assert!(grids_equal_ignoring_trailing_ws(&dump_beams(&g, s).join("\n"), "00100\n00100\n01010\n01010\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn max_beams_tracks_fan_out_peak() -> Result<()> {
+        // r1: 1 -> 2 beams, r2: 2 -> 3 (middle merges), r3: 3 -> 5 (middle passes)
+        let input = "\
+...S...
+...^...
+..^.^..
+.^...^.
+.......
+";
+        let (g, s) = grid_and_start(input)?;
+        assert_eq!(max_simultaneous_beams(&g, s), 5);
+
+        let (g, s) = grid_and_start("S..\n...\n")?;
+        assert_eq!(max_simultaneous_beams(&g, s), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn row_timings_record_one_entry_per_row() -> Result<()> {
+        let input = "\
+...S...
+...^...
+..^.^..
+.^...^.
+.......
+";
+        let (g, s) = grid_and_start(input)?;
+        for timings in [part1_row_timings(&g, s), part2_row_timings(&g, s)] {
+            let rows: Vec<usize> = timings.iter().map(|t| t.row).collect();
+            assert_eq!(rows, [1, 2, 3, 4]);
+
+            let slowest = slowest_rows(&timings, 2);
+            assert_eq!(slowest.len(), 2);
+            assert!(slowest[0].elapsed >= slowest[1].elapsed);
+            assert!(timings.iter().all(|t| t.elapsed <= slowest[0].elapsed));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn part1_report_accounts_for_every_beam_exit() -> Result<()> {
+        // r1: split at col 0 -> left beam exits the side, right beam to col 1
+        // r2: split at col 1 -> beams at cols 0 and 2 reach the bottom
+        let input = "\
+S...
+^...
+.^..
+....
+";
+        let report = process_part1_report(input)?;
+        assert_eq!(
+            report,
+            Part1Report { splits: 2, side_exits: 1, bottom_reaches: 2 }
+        );
+        // No merges: the start beam plus one new beam per split all exit somewhere
+        assert_eq!(report.total_exits(), 1 + report.splits);
+        assert_eq!(report.splits, process_part1(input)?);
+        Ok(())
+    }
+
+    #[test]
+    fn part1_sides_left_splits_exit_at_column_zero() -> Result<()> {
+        // r1: split at col 0 -> left beam exits, right beam to col 1
+        // r2: split at col 1 -> both beams stay (cols 0 and 2)
+        let input = "\
+S...
+^...
+.^..
+....
+";
+        let (g, s) = grid_and_start(input)?;
+        assert_eq!(process_part1_sides_int(&g, s), (1, 2));
+
+        // Only the column-0 split: its left beam is the only one lost
+        let (g, s) = grid_and_start("S..\n^..\n...\n")?;
+        assert_eq!(process_part1_sides_int(&g, s), (0, 1));
+        assert_eq!(process_part1_int(&g, s), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn crop_keeps_margin_and_translates_start() -> Result<()> {
+        let input = "\
+.....S.....
+...........
+.....^.....
+...........
+...........
+";
+        let (g, s) = grid_and_start(input)?;
+        let (cropped, offset) = crop_to_content(&g);
+
+        assert_eq!(offset, (0, 4));
+        assert_eq!(cropped.width, 3);
+        let rendered: String = cropped.rows.iter().map(|row| format!("{}\n", String::from_utf8_lossy(row))).collect();
+        assert!(grids_equal_ignoring_trailing_ws(&rendered, ".S.\n...\n.^."));
+
+        let cs = find_start_column(&cropped.rows[0])?;
+        assert_eq!(cs, s - offset.1);
+        assert_eq!(process_part1_int(&cropped, cs), process_part1_int(&g, s));
+        assert_eq!(process_part2_int(&cropped, cs), process_part2_int(&g, s));
+        Ok(())
+    }
+
+    // -------------------------
+    // Part 2: unit + regression
+    // -------------------------
+
+    #[test]
+    fn part2_tiny_no_splits() -> Result<()> {
+        // r0: S at col 2
+        // r1: no ^ => no splits
+        // r2: no ^ => no splits
+        let input = "\
+..S..
+.....
+.....
+";
+        let (g, s) = grid_and_start(input)?;
+        let ans = process_part2_int(&g, s);
+        assert_eq!(ans, BigUint::from(1u32));
+        Ok(())
+    }
+
+    #[test]
+    fn part2_single_split() -> Result<()> {
+        // r0: S at col 2
+        // r1: ^ at col 2 => split 1 into 2
+        let input = "\
+..S..
+..^..
+.....
+";
+        let (g, s) = grid_and_start(input)?;
+        let ans = process_part2_int(&g, s);
+        assert_eq!(ans, BigUint::from(2u32));
+        Ok(())
+    }
+
+    #[test]
+    fn part2_exceeds_bound_stops_early() -> Result<()> {
+        // Two splits double the count to 4 by row 2; the rest of the grid is
+        // ten rows of open space that the early exit never has to visit.
+        let mut input = String::from("..S..\n..^..\n.^.^.\n");
+        input.push_str(&".....\n".repeat(10));
+        let (g, s) = grid_and_start(&input)?;
+
+        assert_eq!(first_row_exceeding(&g, s, &BigUint::from(3u32)), Some(2));
+        assert!(exceeds_timeline_count(&g, s, &BigUint::from(3u32)));
+        assert!(!exceeds_timeline_count(&g, s, &BigUint::from(4u32)));
+        Ok(())
+    }
+
+    #[test]
+    fn part2_exceeds_bound_despite_edge_splitters() -> Result<()> {
+        // Count reaches 2 on row 1; both beams then hit edge splitters on row 2,
+        // where each keeps its inward branch, so the count stays at 2.
+        let input = "\
+.S.
+.^.
+^.^
+...
+";
+        let (g, s) = grid_and_start(input)?;
+        assert_eq!(process_part2_int(&g, s), BigUint::from(2u32));
+        assert!(exceeds_timeline_count(&g, s, &BigUint::from(1u32)));
+        assert!(!exceeds_timeline_count(&g, s, &BigUint::from(2u32)));
+        assert_eq!(first_row_exceeding(&g, s, &BigUint::from(1u32)), Some(1));
+
+        // One column: a splitter drops both branches, so the count can fall to 0
+        let (g, s) = grid_and_start("S\n.\n^\n")?;
+        assert_eq!(first_row_exceeding(&g, s, &BigUint::zero()), None);
+        let (g, s) = grid_and_start("S\n.\n.\n")?;
+        assert_eq!(first_row_exceeding(&g, s, &BigUint::zero()), Some(2));
+        Ok(())
+    }
+
+    #[test]
+    fn part2_example_from_prompt() -> Result<()> {
+        let input = "\
+.......S.......
+...............
+.......^.......
+...............
+......^.^......
+...............
+.....^.^.^.....
+...............
+....^.^...^....
+...............
+...^.^...^.^...
+...............
+..^...^.....^..
+...............
+.^.^.^.^.^...^.
+...............
+";
+        let ans = process_part2(input)?;
+        assert_eq!(ans, BigUint::from(40u32));
+        Ok(())
+    }
+
+    #[test]
+    fn bottom_distribution_on_prompt_example() -> Result<()> {
+        let (g, s) = grid_and_start(PROMPT_EXAMPLE)?;
+        let dist = bottom_distribution(&g, s);
+        let expected: Vec<(usize, BigUint)> =
+            [(0, 1u32), (2, 2), (4, 10), (6, 11), (8, 11), (10, 2), (11, 1), (12, 1), (14, 1)]
+                .into_iter()
+                .map(|(c, n)| (c, BigUint::from(n)))
+                .collect();
+        assert_eq!(dist, expected);
+        assert_eq!(dist.into_iter().map(|(_, n)| n).sum::<BigUint>(), process_part2_int(&g, s));
+        Ok(())
+    }
+}
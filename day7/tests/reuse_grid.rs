@@ -0,0 +1,39 @@
+use anyhow::Result;
+use day7::{find_start_column, parse_grid, process_part1, process_part1_int, process_part2, process_part2_int};
+use num_bigint::BigUint;
+
+const EXAMPLE: &str = "\
+.......S.......
+...............
+.......^.......
+...............
+......^.^......
+...............
+.....^.^.^.....
+...............
+....^.^...^....
+...............
+...^.^...^.^...
+...............
+..^...^.....^..
+...............
+.^.^.^.^.^...^.
+...............
+";
+
+/// Bench-style reuse: parse once, then run both DPs many times on the same grid.
+#[test]
+fn parsed_grid_is_reusable_across_runs() -> Result<()> {
+    let grid = parse_grid(EXAMPLE)?;
+    let s_col = find_start_column(&grid.rows[0])?;
+
+    for _ in 0..1000 {
+        assert_eq!(process_part1_int(&grid, s_col), 21);
+        assert_eq!(process_part2_int(&grid, s_col), BigUint::from(40u32));
+    }
+
+    // Same answers as the parse-every-time entry points
+    assert_eq!(process_part1(EXAMPLE)?, 21);
+    assert_eq!(process_part2(EXAMPLE)?, BigUint::from(40u32));
+    Ok(())
+}
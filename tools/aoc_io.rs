@@ -0,0 +1,120 @@
+//! Puzzle input fetching and answer formatting shared by every day's binary.
+//!
+//! Not a crate of its own: each day pulls this file in with
+//! `#[path = "../../tools/aoc_io.rs"] mod aoc_io;`. A day uses only the
+//! helpers it needs (string or reader input, `--raw` answers), so the rest is
+//! dead code there.
+#![allow(dead_code)]
+
+use std::env;
+use std::fmt::Display;
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+// Advent of Code 2025 - base URL for fetching puzzle input (day number appended)
+const INPUT_URL_BASE: &str = "https://adventofcode.com/2025/day";
+
+/// Fetches the puzzle input for `day` and passes the body to `f`, returning its result.
+pub fn with_input<T, E: From<io::Error>>(
+    day: u8,
+    f: impl FnOnce(&str) -> Result<T, E>,
+) -> Result<T, E> {
+    with_input_from(|day| fetch_input(day).map_err(E::from), day, f)
+}
+
+/// Same as `with_input`, with the acquisition step injected (e.g. a fixture in tests).
+pub fn with_input_from<T, E>(
+    fetch: impl FnOnce(u8) -> Result<String, E>,
+    day: u8,
+    f: impl FnOnce(&str) -> Result<T, E>,
+) -> Result<T, E> {
+    let body = fetch(day)?;
+    f(&body)
+}
+
+/// Fetches the puzzle input for `day` and passes a reader over the body to `f`,
+/// returning its result.
+pub fn with_input_reader<T, E: From<io::Error>>(
+    day: u8,
+    f: impl FnOnce(&mut dyn BufRead) -> Result<T, E>,
+) -> Result<T, E> {
+    with_input_reader_from(|day| fetch_input_reader(day).map_err(E::from), day, f)
+}
+
+/// Same as `with_input_reader`, with the acquisition step injected.
+pub fn with_input_reader_from<R: BufRead, T, E>(
+    fetch: impl FnOnce(u8) -> Result<R, E>,
+    day: u8,
+    f: impl FnOnce(&mut dyn BufRead) -> Result<T, E>,
+) -> Result<T, E> {
+    let mut reader = fetch(day)?;
+    f(&mut reader)
+}
+
+/// Fetches puzzle input from Advent of Code using the `AOC_SESSION` cookie.
+pub fn fetch_input(day: u8) -> io::Result<String> {
+    let mut body = String::new();
+    fetch_input_reader(day)?.read_to_string(&mut body)?;
+    Ok(body)
+}
+
+/// Like `fetch_input`, but returns a buffered reader over the body: nothing is
+/// read up front, and unlike ureq's `read_to_string` there is no 10MB cap.
+pub fn fetch_input_reader(day: u8) -> io::Result<impl BufRead> {
+    // Retrieve session cookie from environment variable for AOC authentication
+    let session = env::var("AOC_SESSION").map_err(|_| {
+        io::Error::new(io::ErrorKind::NotFound, "AOC_SESSION environment variable is not set")
+    })?;
+
+    let body = ureq::get(&format!("{INPUT_URL_BASE}/{day}/input"))
+        .header("Cookie", &format!("session={session}"))
+        .call()
+        .map_err(io::Error::other)?
+        .into_body()
+        .into_reader();
+
+    Ok(BufReader::new(body))
+}
+
+/// Writes one `format_answer` line to `out`.
+pub fn write_answer(out: &mut impl Write, label: &str, value: impl Display, raw: bool) -> io::Result<()> {
+    writeln!(out, "{}", format_answer(label, value, raw))
+}
+
+/// Formats one answer line: `"{label}: {value}"`, or just the value in raw mode.
+pub fn format_answer(label: &str, value: impl Display, raw: bool) -> String {
+    if raw {
+        value.to_string()
+    } else {
+        format!("{label}: {value}")
+    }
+}
+
+#[test]
+fn with_input_from_passes_input_to_closure() {
+    let result = with_input_from(
+        |day| Ok::<_, String>(format!("day {day}")),
+        3,
+        |body| Ok(body.len()),
+    );
+    assert_eq!(result, Ok(5));
+
+    let lines = with_input_reader_from(
+        |day| Ok::<_, String>(io::Cursor::new(format!("{day}\n{day}\n"))),
+        7,
+        |reader| Ok(reader.lines().count()),
+    );
+    assert_eq!(lines, Ok(2));
+
+    let failed = with_input_from(|_| Err("offline".to_string()), 1, |_| Ok(0));
+    assert_eq!(failed, Err("offline".to_string()));
+}
+
+#[test]
+fn raw_answer_is_bare_value() {
+    assert_eq!(format_answer("Total", 42, true), "42");
+    assert_eq!(format_answer("Total", 42, false), "Total: 42");
+
+    let mut out = Vec::new();
+    write_answer(&mut out, "Total", 42, true).unwrap();
+    assert_eq!(out, b"42\n");
+}
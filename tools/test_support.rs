@@ -0,0 +1,26 @@
+//! Test helpers shared by the grid days (day4, day7).
+//!
+//! Not a crate of its own: each day pulls this file into its test build with
+//! `#[cfg(test)] #[path = "../../tools/test_support.rs"] mod test_support;`.
+
+/// Compares two rendered grids row by row, ignoring trailing spaces on each
+/// row and trailing empty rows, so expected literals don't have to match the
+/// exact line endings of the output.
+pub fn grids_equal_ignoring_trailing_ws(a: &str, b: &str) -> bool {
+    fn rows(s: &str) -> Vec<&str> {
+        let mut rows: Vec<&str> = s.lines().map(|line| line.trim_end()).collect();
+        while rows.last().is_some_and(|row| row.is_empty()) {
+            rows.pop();
+        }
+        rows
+    }
+    rows(a) == rows(b)
+}
+
+#[test]
+fn grids_equal_ignores_trailing_ws() {
+    assert!(grids_equal_ignoring_trailing_ws("@.@\n.@.\n", "@.@  \n.@.\n\n"));
+    assert!(grids_equal_ignoring_trailing_ws("^.^\n.^.", "^.^\n.^.\n"));
+    assert!(!grids_equal_ignoring_trailing_ws("@.@\n.@.\n", "@.@\n\n.@.\n"));
+    assert!(!grids_equal_ignoring_trailing_ws("@.@\n", " @.@\n"));
+}